@@ -1,28 +1,37 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::BinaryHeap,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    iter,
+    marker::PhantomData,
     ops::Add,
 };
 
+use crate::point::{Point2d, PointDist, PointManhattan};
+
+use num_traits::{NumCast, Zero};
+
 /// An object on which we can run Dijkstra's algorithm to retrieve the multiple shortest paths.
 pub trait Dijkstrable {
     /// "Point", "node", or "vertex" type.
     type Point: Copy;
 
     /// Type for the bounds of the search (useful if searching in a `Vec` or similar rather than a
-    /// graph proper). This is only used in the implementation of `neighbours()`, so it can be
-    /// effectively ignored if it's not relevant for that particular implementation.
+    /// graph proper). This is only passed through to `neighbours()`, so it can be effectively
+    /// ignored (eg, by using `()`) if the implementation can get everything it needs from `self`.
     type Bounds: ?Sized + Copy;
 
     /// Type for the distance.
-    type Dist: Copy + Add<Output = Self::Dist>;
+    type Dist: Copy + Add<Output = Self::Dist> + Ord;
 
     /// Type for the elements of a priority queue structure. `PqElement` is often a good choice.
     type PQE: PriorityQueueElement<Point = Self::Point, Dist = Self::Dist>;
 
-    /// Returns an iterator to all the neighbours (or "adjacent points") of the provided `Point`.
-    /// Implementations can ignore the `Bounds` if it's not relevant.
+    /// Returns an iterator to all the neighbours (or "adjacent points") of the provided `Point`,
+    /// along with the distance to each of them. Implementations can ignore the `Bounds` if it's not
+    /// relevant.
     fn neighbours(
+        &self,
         _: Self::Point,
         _: Self::Bounds,
     ) -> impl Iterator<Item = (Self::Point, Self::Dist)>;
@@ -35,33 +44,786 @@ pub trait Dijkstrable {
     fn dist(&self, _: Self::Point) -> Option<Self::Dist>;
 
     /// Sets the distance between the destination and the current `Point`. If the destination and
-    /// the current `Point` are not connected, `None` will be passed in.
+    /// the current `Point` are not connected, `None` will be passed in: this happens for every
+    /// `is_impossible()` `Point` encountered during the search, and, once a search has explored
+    /// everything, for every `Point` from `points()` which wasn't reached.
     fn set_dist(&mut self, _: Self::Point, _: Option<Self::Dist>);
 
-    /// Performs Dijkstra's algorithm. The result is the ending `dist()` value for the starting
-    /// point.
+    /// Returns every `Point` in the search space, so that those which are unreachable can be
+    /// reported to `set_dist()`. The default implementation returns nothing, in which case
+    /// unreachable `Point`s are simply never passed to `set_dist()`.
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        iter::empty()
+    }
+
+    /// Performs Dijkstra's algorithm, filling in `dist()` for every `Point` reachable from `start`
+    /// (including `start` itself, as `start_dist`).
     fn dijkstra(&mut self, start: Self::Point, start_dist: Self::Dist, bounds: Self::Bounds) {
-        let mut q = BinaryHeap::new();
-        q.push(Reverse(Self::PQE::init(start, start_dist)));
-
-        while !q.is_empty() {
-            let curr = q.pop().unwrap();
-
-            for n in Self::neighbours(curr.0.point(), bounds) {
-                let d = if self.is_impossible(n.0) {
-                    None
-                } else {
-                    Some(curr.0.dist() + n.1)
-                };
-                if let Some(dval) = d
-                    && self.dist(n.0).is_none()
-                {
-                    self.set_dist(n.0, d);
-                    q.push(Reverse(Self::PQE::init(n.0, dval)));
+        explore(
+            self,
+            HeapFrontier::<Self::PQE>::new(),
+            [(start, start_dist)],
+            bounds,
+            |_, _, _| {},
+        );
+    }
+
+    /// Performs Dijkstra's algorithm, filling in `dist()` for every reachable `Point` (including
+    /// `start`), and recording every predecessor through which each `Point` can be reached at its
+    /// optimal distance. The returned `Predecessors` can be used to reconstruct one or all of the
+    /// shortest paths to any reached `Point`.
+    fn dijkstra_with_predecessors(
+        &mut self,
+        start: Self::Point,
+        start_dist: Self::Dist,
+        bounds: Self::Bounds,
+    ) -> Predecessors<Self::Point>
+    where
+        Self::Point: Hash + Eq,
+    {
+        explore_with_predecessors(
+            self,
+            HeapFrontier::<Self::PQE>::new(),
+            start,
+            start_dist,
+            bounds,
+        )
+    }
+
+    /// Same as `dijkstra()`, but starts from every `(Point, Dist)` in `starts` at once, each with
+    /// its own starting distance. The resulting `dist()` of each `Point` is the shortest distance
+    /// from any of the `starts` (including its starting distance). Impossible `starts` are skipped,
+    /// and reported to `set_dist()` as `None`.
+    fn dijkstra_multi<I>(&mut self, starts: I, bounds: Self::Bounds)
+    where
+        I: IntoIterator<Item = (Self::Point, Self::Dist)>,
+    {
+        explore(
+            self,
+            HeapFrontier::<Self::PQE>::new(),
+            starts,
+            bounds,
+            |_, _, _| {},
+        );
+    }
+
+    /// Same as `dijkstra_multi()`, but stops as soon as the nearest `Point` for which `is_goal`
+    /// returns `true` is reached, returning that goal along with its distance, or `None` if no goal
+    /// is reachable. `dist()` is only filled in for `Point`s reached before stopping, and unreachable
+    /// `Point`s aren't reported to `set_dist()`.
+    fn dijkstra_until<I, G>(
+        &mut self,
+        starts: I,
+        bounds: Self::Bounds,
+        is_goal: G,
+    ) -> Option<(Self::Point, Self::Dist)>
+    where
+        I: IntoIterator<Item = (Self::Point, Self::Dist)>,
+        G: FnMut(Self::Point) -> bool,
+    {
+        run(
+            &mut DijkstrableSpace {
+                inner: self,
+                bounds,
+            },
+            HeapFrontier::<Self::PQE>::new(),
+            starts,
+            |_, d| d,
+            is_goal,
+            |_, _, _| {},
+        )
+    }
+
+    /// Same as `dijkstra()`, but performs a breadth-first search using a plain queue rather than a
+    /// priority queue. Only gives correct results if every distance returned by `neighbours()` is
+    /// the same.
+    fn bfs(&mut self, start: Self::Point, start_dist: Self::Dist, bounds: Self::Bounds) {
+        explore(
+            self,
+            DequeFrontier::new(false),
+            [(start, start_dist)],
+            bounds,
+            |_, _, _| {},
+        );
+    }
+
+    /// Same as `dijkstra_with_predecessors()`, but with the restrictions of `bfs()`.
+    fn bfs_with_predecessors(
+        &mut self,
+        start: Self::Point,
+        start_dist: Self::Dist,
+        bounds: Self::Bounds,
+    ) -> Predecessors<Self::Point>
+    where
+        Self::Point: Hash + Eq,
+    {
+        explore_with_predecessors(self, DequeFrontier::new(false), start, start_dist, bounds)
+    }
+
+    /// Same as `dijkstra()`, but performs a 0-1 breadth-first search using a double-ended queue
+    /// rather than a priority queue. Only gives correct results if every distance returned by
+    /// `neighbours()` is either zero or the same non-zero value.
+    fn zero_one_bfs(&mut self, start: Self::Point, start_dist: Self::Dist, bounds: Self::Bounds) {
+        explore(
+            self,
+            DequeFrontier::new(true),
+            [(start, start_dist)],
+            bounds,
+            |_, _, _| {},
+        );
+    }
+
+    /// Same as `dijkstra_with_predecessors()`, but with the restrictions of `zero_one_bfs()`.
+    fn zero_one_bfs_with_predecessors(
+        &mut self,
+        start: Self::Point,
+        start_dist: Self::Dist,
+        bounds: Self::Bounds,
+    ) -> Predecessors<Self::Point>
+    where
+        Self::Point: Hash + Eq,
+    {
+        explore_with_predecessors(self, DequeFrontier::new(true), start, start_dist, bounds)
+    }
+
+    /// Performs A* search from `start` until a `Point` for which `is_target` returns `true` is
+    /// reached, returning that `Point` and its distance, or `None` if no target is reachable.
+    /// `heuristic` estimates the remaining distance from a `Point` to the nearest target; the
+    /// result is only guaranteed to be optimal if it never overestimates (see
+    /// `manhattan_heuristic()` and `straight_line_heuristic()`). `dist()` is filled in for every
+    /// `Point` reached along the way, but unlike with `dijkstra()`, these are only guaranteed to be
+    /// optimal for the returned target.
+    fn astar<H, T>(
+        &mut self,
+        start: Self::Point,
+        start_dist: Self::Dist,
+        bounds: Self::Bounds,
+        mut heuristic: H,
+        is_target: T,
+    ) -> Option<(Self::Point, Self::Dist)>
+    where
+        H: FnMut(Self::Point) -> Self::Dist,
+        T: FnMut(Self::Point) -> bool,
+    {
+        run(
+            &mut DijkstrableSpace {
+                inner: self,
+                bounds,
+            },
+            HeapFrontier::<Self::PQE>::new(),
+            [(start, start_dist)],
+            |p, d| d + heuristic(p),
+            is_target,
+            |_, _, _| {},
+        )
+    }
+
+    /// Same as `astar()`, but returns the distance to the target along with a shortest path to it
+    /// (inclusive of both ends).
+    fn astar_with_path<H, T>(
+        &mut self,
+        start: Self::Point,
+        start_dist: Self::Dist,
+        bounds: Self::Bounds,
+        mut heuristic: H,
+        is_target: T,
+    ) -> Option<(Self::Dist, Vec<Self::Point>)>
+    where
+        Self::Point: Hash + Eq,
+        H: FnMut(Self::Point) -> Self::Dist,
+        T: FnMut(Self::Point) -> bool,
+    {
+        let mut preds = Predecessors::new([start]);
+        let (target, dist) = run(
+            &mut DijkstrableSpace {
+                inner: self,
+                bounds,
+            },
+            HeapFrontier::<Self::PQE>::new(),
+            [(start, start_dist)],
+            |p, d| d + heuristic(p),
+            is_target,
+            |from, to, ord| preds.record(from, to, ord),
+        )?;
+        Some((dist, preds.path_to(target)?))
+    }
+}
+
+/// Returns an A* heuristic (for `Dijkstrable::astar()`) estimating the distance to `target` as the
+/// Manhattan distance. This never overestimates if each step moves one unit in one of the
+/// cardinal directions, at a cost of at least 1.
+///
+/// ```
+/// use aoclib_rs::{dijkstra::manhattan_heuristic, point::Point2d};
+/// let h = manhattan_heuristic(Point2d::new(3, 4));
+/// assert_eq!(h(Point2d::new(0, 0)), 7);
+/// ```
+pub fn manhattan_heuristic<P, T>(target: P) -> impl Fn(P) -> T
+where
+    P: PointManhattan<T>,
+{
+    move |p| p.manhattan(&target)
+}
+
+/// Returns an A* heuristic (for `Dijkstrable::astar()`) estimating the distance to `target` as the
+/// straight-line distance, rounded down. This never overestimates as long as the cost of each
+/// step is at least the length of the step. Can panic if the distance doesn't fit in `T`.
+///
+/// ```
+/// use aoclib_rs::{dijkstra::straight_line_heuristic, point::Point2d};
+/// let h = straight_line_heuristic::<_, u32>(Point2d::new(3, 4));
+/// assert_eq!(h(Point2d::new(0, 0)), 5);
+/// assert_eq!(h(Point2d::new(1, 1)), 3);
+/// ```
+pub fn straight_line_heuristic<P, T>(target: P) -> impl Fn(P) -> T
+where
+    P: PointDist,
+    T: NumCast,
+{
+    move |p| T::from(p.dist(&target).floor()).unwrap()
+}
+
+/// Predecessors of each `Point` reached by a search, along all of the shortest paths to it.
+/// Returned by `Dijkstrable::dijkstra_with_predecessors()`.
+///
+/// If the search contains cycles with a total distance of zero, the predecessors can form a cycle
+/// too, in which case `all_paths_to()` doesn't terminate.
+#[derive(Clone, Debug)]
+pub struct Predecessors<P> {
+    starts: HashSet<P>,
+    preds: HashMap<P, Vec<P>>,
+}
+
+impl<P: Copy + Hash + Eq> Predecessors<P> {
+    fn new<I: IntoIterator<Item = P>>(starts: I) -> Self {
+        Self {
+            starts: starts.into_iter().collect(),
+            preds: HashMap::new(),
+        }
+    }
+
+    /// Records that `to` was reached from `from` with a distance better than (`Ordering::Less`) or
+    /// equal to (`Ordering::Equal`) the best seen so far.
+    fn record(&mut self, from: P, to: P, ord: Ordering) {
+        if self.starts.contains(&to) {
+            if ord == Ordering::Equal {
+                return;
+            }
+            self.starts.remove(&to);
+        }
+
+        let v = self.preds.entry(to).or_default();
+        if ord == Ordering::Less {
+            v.clear();
+        }
+        v.push(from);
+    }
+
+    /// Whether `p` was reached by the search (including if it's a starting point).
+    pub fn is_reached(&self, p: P) -> bool {
+        self.starts.contains(&p) || self.preds.contains_key(&p)
+    }
+
+    /// Every `Point` immediately before `p` on some shortest path to `p`. Empty for starting
+    /// points and unreached points.
+    pub fn predecessors(&self, p: P) -> &[P] {
+        self.preds.get(&p).map_or(&[], |v| v.as_slice())
+    }
+
+    /// Returns one shortest path from a starting point to `p` (inclusive of both ends), or `None`
+    /// if `p` wasn't reached.
+    pub fn path_to(&self, p: P) -> Option<Vec<P>> {
+        if !self.is_reached(p) {
+            return None;
+        }
+
+        let mut path = vec![p];
+        let mut curr = p;
+        while let Some(&prev) = self.predecessors(curr).first() {
+            path.push(prev);
+            curr = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns every shortest path from a starting point to `p` (inclusive of both ends). Empty if
+    /// `p` wasn't reached. The number of paths can grow exponentially, so prefer
+    /// `on_any_path_to()` if only the set of `Point`s involved is needed.
+    pub fn all_paths_to(&self, p: P) -> Vec<Vec<P>> {
+        if !self.is_reached(p) {
+            return Vec::new();
+        }
+
+        let preds = self.predecessors(p);
+        if preds.is_empty() {
+            return vec![vec![p]];
+        }
+
+        let mut paths = Vec::new();
+        for &prev in preds {
+            for mut path in self.all_paths_to(prev) {
+                path.push(p);
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Returns every `Point` which lies on at least one shortest path to `p`, including `p`
+    /// itself. Empty if `p` wasn't reached.
+    pub fn on_any_path_to(&self, p: P) -> HashSet<P> {
+        let mut seen = HashSet::new();
+        if !self.is_reached(p) {
+            return seen;
+        }
+
+        let mut stack = vec![p];
+        seen.insert(p);
+        while let Some(curr) = stack.pop() {
+            for &prev in self.predecessors(curr) {
+                if seen.insert(prev) {
+                    stack.push(prev);
+                }
+            }
+        }
+        seen
+    }
+}
+
+/// The parts of a search problem needed by `run()`.
+trait SearchSpace {
+    type Point: Copy;
+    type Dist: Copy + Add<Output = Self::Dist> + Ord;
+
+    /// Appends every neighbour of `p`, along with the distance to it, to `out`.
+    fn neighbours(&mut self, p: Self::Point, out: &mut Vec<(Self::Point, Self::Dist)>);
+    fn is_impossible(&self, p: Self::Point) -> bool;
+    fn dist(&self, p: Self::Point) -> Option<Self::Dist>;
+    fn set_dist(&mut self, p: Self::Point, d: Option<Self::Dist>);
+}
+
+/// Adapts a `Dijkstrable` to a `SearchSpace`.
+struct DijkstrableSpace<'a, S: Dijkstrable + ?Sized> {
+    inner: &'a mut S,
+    bounds: S::Bounds,
+}
+
+impl<S: Dijkstrable + ?Sized> SearchSpace for DijkstrableSpace<'_, S> {
+    type Point = S::Point;
+    type Dist = S::Dist;
+
+    fn neighbours(&mut self, p: Self::Point, out: &mut Vec<(Self::Point, Self::Dist)>) {
+        out.extend(self.inner.neighbours(p, self.bounds));
+    }
+
+    fn is_impossible(&self, p: Self::Point) -> bool {
+        self.inner.is_impossible(p)
+    }
+
+    fn dist(&self, p: Self::Point) -> Option<Self::Dist> {
+        self.inner.dist(p)
+    }
+
+    fn set_dist(&mut self, p: Self::Point, d: Option<Self::Dist>) {
+        self.inner.set_dist(p, d);
+    }
+}
+
+/// Explores everything reachable from `starts`, then reports unreachable `Point`s.
+fn explore<S, Q, I, F>(s: &mut S, frontier: Q, starts: I, bounds: S::Bounds, on_relax: F)
+where
+    S: Dijkstrable + ?Sized,
+    Q: Frontier<S::Point, S::Dist>,
+    I: IntoIterator<Item = (S::Point, S::Dist)>,
+    F: FnMut(S::Point, S::Point, Ordering),
+{
+    run(
+        &mut DijkstrableSpace { inner: s, bounds },
+        frontier,
+        starts,
+        |_, d| d,
+        |_| false,
+        on_relax,
+    );
+    set_unreachable(s);
+}
+
+fn explore_with_predecessors<S, Q>(
+    s: &mut S,
+    frontier: Q,
+    start: S::Point,
+    start_dist: S::Dist,
+    bounds: S::Bounds,
+) -> Predecessors<S::Point>
+where
+    S: Dijkstrable + ?Sized,
+    S::Point: Hash + Eq,
+    Q: Frontier<S::Point, S::Dist>,
+{
+    let mut preds = Predecessors::new([start]);
+    explore(
+        s,
+        frontier,
+        [(start, start_dist)],
+        bounds,
+        |from, to, ord| preds.record(from, to, ord),
+    );
+    preds
+}
+
+/// Passes `None` to `set_dist()` for every `Point` from `points()` without a `dist()`.
+fn set_unreachable<S: Dijkstrable + ?Sized>(s: &mut S) {
+    let unreachable: Vec<_> = s.points().filter(|&p| s.dist(p).is_none()).collect();
+    for p in unreachable {
+        s.set_dist(p, None);
+    }
+}
+
+/// A `SearchSpace` defined by a neighbour closure, storing distances in a `HashMap`.
+struct ClosureSpace<P, D, N> {
+    dists: HashMap<P, D>,
+    neighbours: N,
+}
+
+impl<P, D, N, I> SearchSpace for ClosureSpace<P, D, N>
+where
+    P: Copy + Hash + Eq,
+    D: Copy + Add<Output = D> + Ord,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = (P, D)>,
+{
+    type Point = P;
+    type Dist = D;
+
+    fn neighbours(&mut self, p: P, out: &mut Vec<(P, D)>) {
+        out.extend((self.neighbours)(p));
+    }
+
+    fn is_impossible(&self, _: P) -> bool {
+        false
+    }
+
+    fn dist(&self, p: P) -> Option<D> {
+        self.dists.get(&p).copied()
+    }
+
+    fn set_dist(&mut self, p: P, d: Option<D>) {
+        match d {
+            Some(d) => self.dists.insert(p, d),
+            None => self.dists.remove(&p),
+        };
+    }
+}
+
+/// Runs Dijkstra's algorithm from `start` without needing to implement `Dijkstrable`.
+/// `neighbours` returns every neighbour of a point, along with the distance to it, and may borrow
+/// (or even mutate) the caller's data. The search stops as soon as a point for which `is_goal`
+/// returns `true` is reached; pass `|_| false` to explore everything reachable.
+///
+/// ```
+/// use aoclib_rs::dijkstra::search;
+/// use std::collections::HashMap;
+///
+/// let edges = HashMap::from([
+///     ('a', vec![('b', 7), ('c', 2)]),
+///     ('b', vec![('d', 1)]),
+///     ('c', vec![('b', 3), ('d', 8)]),
+/// ]);
+/// let r = search(
+///     'a',
+///     |p| edges.get(&p).cloned().unwrap_or_default(),
+///     |p| p == 'd',
+/// );
+/// assert_eq!(r.goal(), Some(('d', 6)));
+/// assert_eq!(r.goal_path(), Some(vec!['a', 'c', 'b', 'd']));
+/// assert_eq!(r.dist('b'), Some(5));
+/// ```
+pub fn search<P, D, N, I, G>(start: P, neighbours: N, is_goal: G) -> SearchResult<P, D>
+where
+    P: Copy + Hash + Eq,
+    D: Copy + Add<Output = D> + Ord + Zero,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = (P, D)>,
+    G: FnMut(P) -> bool,
+{
+    closure_search(
+        HeapFrontier::<PqElement<P, D>>::new(),
+        start,
+        neighbours,
+        is_goal,
+    )
+}
+
+/// Same as `search()`, but performs a breadth-first search, where every step has a distance of 1.
+/// `neighbours` only needs to return the neighbouring points.
+///
+/// ```
+/// use aoclib_rs::{dijkstra::bfs_search, dir::{Dir4, Direction}};
+///
+/// let map = ["..#", "#..", "..."];
+/// let open = |(x, y): (usize, usize)| map[y].as_bytes()[x] == b'.';
+/// let r = bfs_search(
+///     (0, 0),
+///     |p| Dir4::iter_valid_usizes_deltas(p, (3, 3)).filter(|&n| open(n)),
+///     |_| false,
+/// );
+/// assert_eq!(r.dist((0, 2)), Some(4));
+/// assert_eq!(r.dists().len(), 7);
+/// ```
+pub fn bfs_search<P, N, I, G>(start: P, mut neighbours: N, is_goal: G) -> SearchResult<P, usize>
+where
+    P: Copy + Hash + Eq,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = P>,
+    G: FnMut(P) -> bool,
+{
+    closure_search(
+        DequeFrontier::new(false),
+        start,
+        |p| neighbours(p).into_iter().map(|n| (n, 1)),
+        is_goal,
+    )
+}
+
+/// Same as `search()`, but performs a 0-1 breadth-first search. Only gives correct results if
+/// every distance returned by `neighbours` is either zero or the same non-zero value.
+///
+/// ```
+/// use aoclib_rs::dijkstra::zero_one_bfs_search;
+///
+/// // Moving right along a conveyor belt is free.
+/// let r = zero_one_bfs_search(
+///     0,
+///     |p: i32| [(p - 1, 1), (p + 1, if p < 5 { 0 } else { 1 })],
+///     |p| p == 7,
+/// );
+/// assert_eq!(r.goal(), Some((7, 2)));
+/// ```
+pub fn zero_one_bfs_search<P, D, N, I, G>(start: P, neighbours: N, is_goal: G) -> SearchResult<P, D>
+where
+    P: Copy + Hash + Eq,
+    D: Copy + Add<Output = D> + Ord + Zero,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = (P, D)>,
+    G: FnMut(P) -> bool,
+{
+    closure_search(DequeFrontier::new(true), start, neighbours, is_goal)
+}
+
+fn closure_search<P, D, Q, N, I, G>(
+    frontier: Q,
+    start: P,
+    neighbours: N,
+    is_goal: G,
+) -> SearchResult<P, D>
+where
+    P: Copy + Hash + Eq,
+    D: Copy + Add<Output = D> + Ord + Zero,
+    Q: Frontier<P, D>,
+    N: FnMut(P) -> I,
+    I: IntoIterator<Item = (P, D)>,
+    G: FnMut(P) -> bool,
+{
+    let mut space = ClosureSpace {
+        dists: HashMap::new(),
+        neighbours,
+    };
+    let mut preds = Predecessors::new([start]);
+    let goal = run(
+        &mut space,
+        frontier,
+        [(start, D::zero())],
+        |_, d| d,
+        is_goal,
+        |from, to, ord| preds.record(from, to, ord),
+    );
+    SearchResult {
+        dists: space.dists,
+        preds,
+        goal,
+    }
+}
+
+/// Result of `search()`, `bfs_search()` or `zero_one_bfs_search()`.
+#[derive(Clone, Debug)]
+pub struct SearchResult<P, D> {
+    dists: HashMap<P, D>,
+    preds: Predecessors<P>,
+    goal: Option<(P, D)>,
+}
+
+impl<P: Copy + Hash + Eq, D: Copy> SearchResult<P, D> {
+    /// Returns the shortest distance from the start to `p`, or `None` if it wasn't reached. If the
+    /// search stopped early at a goal, distances to points other than the goal may not be optimal.
+    pub fn dist(&self, p: P) -> Option<D> {
+        self.dists.get(&p).copied()
+    }
+
+    /// Distances to every point reached (including the start). Unless the search stopped early at
+    /// a goal, this is everything reachable from the start.
+    pub fn dists(&self) -> &HashMap<P, D> {
+        &self.dists
+    }
+
+    /// Returns a shortest path from the start to `p` (inclusive of both ends), or `None` if it
+    /// wasn't reached.
+    pub fn path_to(&self, p: P) -> Option<Vec<P>> {
+        self.preds.path_to(p)
+    }
+
+    pub fn predecessors(&self) -> &Predecessors<P> {
+        &self.preds
+    }
+
+    /// The goal which was reached, along with its distance, or `None` if no goal was reachable.
+    pub fn goal(&self) -> Option<(P, D)> {
+        self.goal
+    }
+
+    /// Returns a shortest path from the start to the goal (inclusive of both ends), or `None` if
+    /// no goal was reachable.
+    pub fn goal_path(&self) -> Option<Vec<P>> {
+        self.path_to(self.goal?.0)
+    }
+}
+
+/// Queue of points waiting to be expanded by `run()`.
+trait Frontier<P, D> {
+    /// Adds `p` with the given `priority`. `parent_priority` is the priority of the point from
+    /// which `p` was reached, or `None` for starting points.
+    fn push(&mut self, p: P, priority: D, parent_priority: Option<D>);
+
+    /// Removes and returns the next point to expand, along with the priority it was pushed with.
+    fn pop(&mut self) -> Option<(P, D)>;
+}
+
+/// `Frontier` which pops the lowest priority first.
+struct HeapFrontier<Q>(MinHeap<Q>);
+
+impl<Q: Ord> HeapFrontier<Q> {
+    fn new() -> Self {
+        Self(MinHeap::new())
+    }
+}
+
+impl<Q: PriorityQueueElement> Frontier<Q::Point, Q::Dist> for HeapFrontier<Q> {
+    fn push(&mut self, p: Q::Point, priority: Q::Dist, _: Option<Q::Dist>) {
+        self.0.push(Q::init(p, priority));
+    }
+
+    fn pop(&mut self) -> Option<(Q::Point, Q::Dist)> {
+        self.0.pop().map(|e| (e.point(), e.dist()))
+    }
+}
+
+/// `Frontier` which pops in first-in, first-out order, except that (if `zero_one` is set) points
+/// with the same priority as their parent jump to the front of the queue.
+struct DequeFrontier<P, D> {
+    q: VecDeque<(P, D)>,
+    zero_one: bool,
+}
+
+impl<P, D> DequeFrontier<P, D> {
+    fn new(zero_one: bool) -> Self {
+        Self {
+            q: VecDeque::new(),
+            zero_one,
+        }
+    }
+}
+
+impl<P, D: PartialEq> Frontier<P, D> for DequeFrontier<P, D> {
+    fn push(&mut self, p: P, priority: D, parent_priority: Option<D>) {
+        if self.zero_one && parent_priority.is_some_and(|pp| pp == priority) {
+            self.q.push_front((p, priority));
+        } else {
+            self.q.push_back((p, priority));
+        }
+    }
+
+    fn pop(&mut self) -> Option<(P, D)> {
+        self.q.pop_front()
+    }
+}
+
+/// Best-first search over a `SearchSpace`. Points are popped from `frontier` in order of
+/// `priority(point, dist)`, so with a `HeapFrontier` this is Dijkstra's algorithm if `priority`
+/// returns `dist`, or A* if it adds a heuristic. With a `DequeFrontier` it's a (0-1) BFS.
+/// The search starts from every point in `starts`, each with its own starting distance. Stops as
+/// soon as a point for which `is_target` returns `true` is popped, returning it along
+/// with its distance. `on_relax(from, to, ord)` is called whenever `to` is reached from `from` with
+/// a distance better than (`Ordering::Less`) or equal to (`Ordering::Equal`) the best seen so far.
+fn run<S, Q, I, H, T, F>(
+    space: &mut S,
+    mut frontier: Q,
+    starts: I,
+    mut priority: H,
+    mut is_target: T,
+    mut on_relax: F,
+) -> Option<(S::Point, S::Dist)>
+where
+    S: SearchSpace,
+    Q: Frontier<S::Point, S::Dist>,
+    I: IntoIterator<Item = (S::Point, S::Dist)>,
+    H: FnMut(S::Point, S::Dist) -> S::Dist,
+    T: FnMut(S::Point) -> bool,
+    F: FnMut(S::Point, S::Point, Ordering),
+{
+    for (p, d) in starts {
+        if space.is_impossible(p) {
+            space.set_dist(p, None);
+            continue;
+        }
+        if space.dist(p).is_some_and(|best| best <= d) {
+            continue;
+        }
+        space.set_dist(p, Some(d));
+        frontier.push(p, priority(p, d), None);
+    }
+
+    let mut neighbours = Vec::new();
+    while let Some((p, popped_priority)) = frontier.pop() {
+        let Some(d) = space.dist(p) else {
+            continue;
+        };
+        let curr_priority = priority(p, d);
+        if popped_priority > curr_priority {
+            // Stale entry: `p` has since been reached more cheaply.
+            continue;
+        }
+        if is_target(p) {
+            return Some((p, d));
+        }
+
+        neighbours.clear();
+        space.neighbours(p, &mut neighbours);
+        for &(n, w) in &neighbours {
+            if space.is_impossible(n) {
+                if space.dist(n).is_none() {
+                    space.set_dist(n, None);
                 }
+                continue;
+            }
+
+            let nd = d + w;
+            let ord = match space.dist(n) {
+                None => Ordering::Less,
+                Some(best) => nd.cmp(&best),
+            };
+            if ord == Ordering::Greater {
+                continue;
+            }
+
+            on_relax(p, n, ord);
+            if ord == Ordering::Less {
+                space.set_dist(n, Some(nd));
+                frontier.push(n, priority(n, nd), Some(curr_priority));
             }
         }
     }
+
+    None
 }
 
 /// Element of a priority queue.
@@ -78,7 +840,8 @@ pub trait PriorityQueueElement: Ord + Copy + Sized {
 }
 
 /// Concrete implementation of `PriorityQueueElement`. Comparison and equality operations only care
-/// about `val`.
+/// about `val`, so the order in which points with equal values are popped is arbitrary. Use
+/// `TieBreakPqElement` if that matters.
 #[derive(Copy, Clone)]
 pub struct PqElement<Point, Value>
 where
@@ -146,3 +909,484 @@ where
         self.val
     }
 }
+
+/// Secondary ordering for `TieBreakPqElement`, used to decide between points with equal values.
+/// Implement this on a (typically zero-sized) type to supply a custom tiebreak.
+pub trait TieBreak<Point> {
+    fn cmp(a: &Point, b: &Point) -> Ordering;
+}
+
+/// `TieBreak` using the `Ord` implementation of the point itself.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PointOrder;
+
+impl<Point: Ord> TieBreak<Point> for PointOrder {
+    fn cmp(a: &Point, b: &Point) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// `TieBreak` for `(x, y)` positions (interpreted as in `position_2d()`) which orders them
+/// left-to-right, top-to-bottom, ie, by `y`, then by `x`.
+///
+/// ```
+/// use aoclib_rs::dijkstra::{ReadingOrder, TieBreak};
+/// use std::cmp::Ordering;
+/// assert_eq!(ReadingOrder::cmp(&(5, 0), &(0, 1)), Ordering::Less);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ReadingOrder;
+
+impl<T: Ord> TieBreak<(T, T)> for ReadingOrder {
+    fn cmp(a: &(T, T), b: &(T, T)) -> Ordering {
+        (&a.1, &a.0).cmp(&(&b.1, &b.0))
+    }
+}
+
+impl<T: Copy + Ord> TieBreak<Point2d<T>> for ReadingOrder {
+    fn cmp(a: &Point2d<T>, b: &Point2d<T>) -> Ordering {
+        (a.y(), a.x()).cmp(&(b.y(), b.x()))
+    }
+}
+
+/// Implementation of `PriorityQueueElement` which compares by `val`, then breaks ties by comparing
+/// the points with `Tie`. In a `MinHeap` (as used by `Dijkstrable`), lesser points are popped
+/// first, so with `ReadingOrder`, equidistant points are popped in reading order.
+pub struct TieBreakPqElement<Point, Value, Tie> {
+    point: Point,
+    val: Value,
+    tie: PhantomData<fn() -> Tie>,
+}
+
+impl<Point: Copy, Value: Copy, Tie> Clone for TieBreakPqElement<Point, Value, Tie> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Point: Copy, Value: Copy, Tie> Copy for TieBreakPqElement<Point, Value, Tie> {}
+
+impl<Point, Value, Tie> Ord for TieBreakPqElement<Point, Value, Tie>
+where
+    Value: Ord,
+    Tie: TieBreak<Point>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.val
+            .cmp(&other.val)
+            .then_with(|| Tie::cmp(&self.point, &other.point))
+    }
+}
+
+impl<Point, Value, Tie> PartialOrd for TieBreakPqElement<Point, Value, Tie>
+where
+    Value: Ord,
+    Tie: TieBreak<Point>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Point, Value, Tie> PartialEq for TieBreakPqElement<Point, Value, Tie>
+where
+    Value: Ord,
+    Tie: TieBreak<Point>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<Point, Value, Tie> Eq for TieBreakPqElement<Point, Value, Tie>
+where
+    Value: Ord,
+    Tie: TieBreak<Point>,
+{
+}
+
+impl<Point, Value, Tie> PriorityQueueElement for TieBreakPqElement<Point, Value, Tie>
+where
+    Point: Copy,
+    Value: Copy + Add<Output = Value> + Ord,
+    Tie: TieBreak<Point>,
+{
+    type Point = Point;
+    type Dist = Value;
+
+    fn init(p: Self::Point, d: Self::Dist) -> Self {
+        TieBreakPqElement {
+            point: p,
+            val: d,
+            tie: PhantomData,
+        }
+    }
+
+    fn point(&self) -> Self::Point {
+        self.point
+    }
+
+    fn dist(&self) -> Self::Dist {
+        self.val
+    }
+}
+
+/// A priority queue which pops the least element first, wrapping a `BinaryHeap` of `Reverse`s.
+///
+/// ```
+/// use aoclib_rs::dijkstra::MinHeap;
+/// let mut h: MinHeap<_> = [5, 1, 3].into_iter().collect();
+/// h.push(2);
+/// assert_eq!(h.peek(), Some(&1));
+/// assert_eq!(h.into_sorted_vec(), [1, 2, 3, 5]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MinHeap<T>(BinaryHeap<Reverse<T>>);
+
+impl<T: Ord> MinHeap<T> {
+    pub fn new() -> Self {
+        Self(BinaryHeap::new())
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.0.push(Reverse(item));
+    }
+
+    /// Removes and returns the least element, or `None` if empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.0.pop().map(|Reverse(item)| item)
+    }
+
+    /// Returns the least element, or `None` if empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.0.peek().map(|Reverse(item)| item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Consumes the `MinHeap`, returning its elements in ascending order.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut v: Vec<T> = self.0.into_iter().map(|Reverse(item)| item).collect();
+        v.sort();
+        v
+    }
+}
+
+impl<T: Ord> Default for MinHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MinHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Reverse).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dir::{Dir4, Direction};
+
+    /// Grid where `'#'` is a wall and every other cell costs its digit value to enter.
+    struct CostGrid {
+        costs: Vec<Vec<Option<u32>>>,
+        dists: Vec<Vec<Option<u32>>>,
+        unreachable: HashSet<(usize, usize)>,
+    }
+
+    impl CostGrid {
+        fn new(lines: &[&str]) -> Self {
+            CostGrid {
+                costs: lines
+                    .iter()
+                    .map(|l| l.chars().map(|c| c.to_digit(10)).collect())
+                    .collect(),
+                dists: vec![vec![None; lines[0].len()]; lines.len()],
+                unreachable: HashSet::new(),
+            }
+        }
+
+        fn size(&self) -> (usize, usize) {
+            (self.costs[0].len(), self.costs.len())
+        }
+    }
+
+    impl Dijkstrable for CostGrid {
+        type Point = (usize, usize);
+        type Bounds = ();
+        type Dist = u32;
+        type PQE = PqElement<(usize, usize), u32>;
+
+        fn neighbours(
+            &self,
+            p: (usize, usize),
+            _: (),
+        ) -> impl Iterator<Item = ((usize, usize), u32)> {
+            Dir4::iter_valid_usizes_deltas(p, self.size())
+                .map(|n| (n, self.costs[n.1][n.0].unwrap_or(0)))
+        }
+
+        fn is_impossible(&self, p: (usize, usize)) -> bool {
+            self.costs[p.1][p.0].is_none()
+        }
+
+        fn dist(&self, p: (usize, usize)) -> Option<u32> {
+            self.dists[p.1][p.0]
+        }
+
+        fn set_dist(&mut self, p: (usize, usize), d: Option<u32>) {
+            self.dists[p.1][p.0] = d;
+            if d.is_none() {
+                self.unreachable.insert(p);
+            }
+        }
+
+        fn points(&self) -> impl Iterator<Item = (usize, usize)> {
+            let (width, height) = self.size();
+            (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+        }
+    }
+
+    #[test]
+    fn test_predecessors_all_paths() {
+        let mut g = CostGrid::new(&["111", "111", "111"]);
+        let preds = g.dijkstra_with_predecessors((0, 0), 0, ());
+        assert_eq!(g.dist((2, 2)), Some(4));
+        assert_eq!(g.dist((0, 0)), Some(0));
+        assert_eq!(preds.path_to((2, 2)).unwrap().len(), 5);
+        assert_eq!(preds.all_paths_to((2, 2)).len(), 6);
+        assert_eq!(preds.on_any_path_to((2, 2)).len(), 9);
+        assert_eq!(preds.all_paths_to((0, 0)), [vec![(0, 0)]]);
+    }
+
+    #[test]
+    fn test_predecessors_weighted_with_walls() {
+        let mut g = CostGrid::new(&["191", "1#1", "111"]);
+        let preds = g.dijkstra_with_predecessors((0, 0), 0, ());
+        assert_eq!(g.dist((2, 0)), Some(6));
+        assert_eq!(
+            preds.path_to((2, 0)).unwrap(),
+            [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0)]
+        );
+        assert_eq!(preds.all_paths_to((2, 0)).len(), 1);
+        assert!(!preds.is_reached((1, 1)));
+        assert_eq!(preds.path_to((1, 1)), None);
+        assert!(preds.on_any_path_to((1, 1)).is_empty());
+    }
+
+    #[test]
+    fn test_dijkstra_weighted_and_unreachable() {
+        let mut g = CostGrid::new(&["1191", "9#11", "11#1", "1#1#"]);
+        g.dijkstra((0, 0), 0, ());
+        assert_eq!(g.dist((0, 0)), Some(0));
+        assert_eq!(g.dist((2, 0)), Some(10));
+        assert_eq!(g.dist((2, 1)), Some(11));
+        assert_eq!(g.dist((3, 2)), Some(13));
+        assert_eq!(g.dist((1, 2)), Some(11));
+        assert_eq!(g.dist((2, 3)), None);
+
+        // Walls, plus (2, 3), which is surrounded by walls.
+        assert_eq!(
+            g.unreachable,
+            HashSet::from([(1, 1), (2, 2), (1, 3), (3, 3), (2, 3)])
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_multi() {
+        let lines = ["1111", "1#11", "1111"];
+        let mut g = CostGrid::new(&lines);
+        g.dijkstra_multi([((0, 0), 0), ((3, 2), 0), ((3, 0), 5)], ());
+        assert_eq!(g.dist((0, 0)), Some(0));
+        assert_eq!(g.dist((3, 0)), Some(2));
+        assert_eq!(g.dist((1, 2)), Some(2));
+        assert_eq!(g.dist((0, 2)), Some(2));
+        assert_eq!(g.dist((2, 0)), Some(2));
+        assert_eq!(g.unreachable, HashSet::from([(1, 1)]));
+    }
+
+    #[test]
+    fn test_dijkstra_multi_impossible_start() {
+        let lines = ["1111", "1#11", "1111"];
+        let mut g = CostGrid::new(&lines);
+        g.dijkstra_multi([((0, 1), 0), ((1, 1), 5)], ());
+        assert_eq!(g.dist((1, 1)), None);
+        assert_eq!(g.dist((0, 1)), Some(0));
+        assert_eq!(g.dist((3, 1)), Some(5));
+        assert_eq!(g.unreachable, HashSet::from([(1, 1)]));
+
+        let mut g = CostGrid::new(&lines);
+        assert_eq!(
+            g.dijkstra_until([((0, 1), 0), ((1, 1), 5)], (), |p| p == (2, 1)),
+            Some(((2, 1), 4))
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_until() {
+        let lines = ["1911", "1#11", "1111"];
+        let mut g = CostGrid::new(&lines);
+        let exits = [(3, 0), (1, 0)];
+        assert_eq!(
+            g.dijkstra_until([((0, 0), 0)], (), |p| exits.contains(&p)),
+            Some(((3, 0), 7))
+        );
+        assert_eq!(g.dist((0, 0)), Some(0));
+
+        let mut g = CostGrid::new(&lines);
+        assert_eq!(g.dijkstra_until([((0, 0), 0)], (), |p| p == (1, 1)), None);
+        assert!(g.unreachable.contains(&(1, 1)));
+    }
+
+    #[test]
+    fn test_bfs_matches_dijkstra() {
+        let lines = ["11111", "1#1#1", "11#11", "#1111"];
+        let mut expected = CostGrid::new(&lines);
+        let expected_preds = expected.dijkstra_with_predecessors((0, 0), 0, ());
+
+        let mut g = CostGrid::new(&lines);
+        let preds = g.bfs_with_predecessors((0, 0), 0, ());
+        assert_eq!(g.dists, expected.dists);
+        assert_eq!(g.unreachable, expected.unreachable);
+        assert_eq!(
+            preds.on_any_path_to((4, 3)),
+            expected_preds.on_any_path_to((4, 3))
+        );
+
+        let mut g = CostGrid::new(&lines);
+        g.zero_one_bfs((0, 0), 0, ());
+        assert_eq!(g.dists, expected.dists);
+    }
+
+    #[test]
+    fn test_zero_one_bfs_matches_dijkstra() {
+        let lines = ["10000", "0#1#1", "01#01", "#1110"];
+        let mut expected = CostGrid::new(&lines);
+        expected.dijkstra((0, 0), 0, ());
+        assert_eq!(expected.dist((4, 3)), Some(2));
+
+        let mut g = CostGrid::new(&lines);
+        let preds = g.zero_one_bfs_with_predecessors((0, 0), 0, ());
+        assert_eq!(g.dists, expected.dists);
+        assert_eq!(preds.path_to((4, 3)).unwrap().len(), 8);
+    }
+
+    /// Open 3x3 grid, where every step costs 1, popping equidistant points in the order given by
+    /// `Tie`.
+    struct OpenGrid<Tie> {
+        dists: HashMap<(usize, usize), u32>,
+        tie: PhantomData<Tie>,
+    }
+
+    impl<Tie: TieBreak<(usize, usize)>> Dijkstrable for OpenGrid<Tie> {
+        type Point = (usize, usize);
+        type Bounds = ();
+        type Dist = u32;
+        type PQE = TieBreakPqElement<(usize, usize), u32, Tie>;
+
+        fn neighbours(
+            &self,
+            p: (usize, usize),
+            _: (),
+        ) -> impl Iterator<Item = ((usize, usize), u32)> {
+            Dir4::iter_valid_usizes_deltas(p, (3, 3)).map(|n| (n, 1))
+        }
+
+        fn is_impossible(&self, _: (usize, usize)) -> bool {
+            false
+        }
+
+        fn dist(&self, p: (usize, usize)) -> Option<u32> {
+            self.dists.get(&p).copied()
+        }
+
+        fn set_dist(&mut self, p: (usize, usize), d: Option<u32>) {
+            if let Some(d) = d {
+                self.dists.insert(p, d);
+            }
+        }
+    }
+
+    fn first_edge_cell<Tie: TieBreak<(usize, usize)>>() -> Option<((usize, usize), u32)> {
+        let mut g = OpenGrid::<Tie> {
+            dists: HashMap::new(),
+            tie: PhantomData,
+        };
+        g.dijkstra_until([((1, 1), 0)], (), |p| p != (1, 1))
+    }
+
+    #[test]
+    fn test_tie_break() {
+        struct ReverseReadingOrder;
+
+        impl TieBreak<(usize, usize)> for ReverseReadingOrder {
+            fn cmp(a: &(usize, usize), b: &(usize, usize)) -> Ordering {
+                ReadingOrder::cmp(b, a)
+            }
+        }
+
+        assert_eq!(first_edge_cell::<ReadingOrder>(), Some(((1, 0), 1)));
+        assert_eq!(first_edge_cell::<PointOrder>(), Some(((0, 1), 1)));
+        assert_eq!(first_edge_cell::<ReverseReadingOrder>(), Some(((1, 2), 1)));
+    }
+
+    #[test]
+    fn test_astar() {
+        let lines = ["19111", "1#1#1", "11111"];
+        let target = Point2d::new(4, 0);
+        let h = manhattan_heuristic::<Point2d<i64>, i64>(target);
+        let heuristic = |(x, y): (usize, usize)| {
+            u32::try_from(h(Point2d::new(
+                i64::try_from(x).unwrap(),
+                i64::try_from(y).unwrap(),
+            )))
+            .unwrap()
+        };
+
+        let mut g = CostGrid::new(&lines);
+        let r = g.astar((0, 0), 0, (), heuristic, |p| p == (4, 0));
+        assert_eq!(r, Some(((4, 0), 8)));
+
+        let mut g = CostGrid::new(&lines);
+        let (d, path) = g
+            .astar_with_path((0, 0), 0, (), heuristic, |p| p == (4, 0))
+            .unwrap();
+        assert_eq!(d, 8);
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(4, 0)));
+
+        let mut g = CostGrid::new(&lines);
+        assert_eq!(g.astar((0, 0), 0, (), |_| 0, |p| p == (1, 1)), None);
+    }
+
+    #[test]
+    fn test_search_explores_everything() {
+        // Collatz-like graph: from n, you can go to n / 2 (if even) for 1, or 3n + 1 for 10.
+        let r = search(
+            6u64,
+            |n| {
+                let mut v = vec![(3 * n + 1, 10)];
+                if n.is_multiple_of(2) {
+                    v.push((n / 2, 1));
+                }
+                v.into_iter().filter(|&(n, _)| n < 100)
+            },
+            |_| false,
+        );
+        assert_eq!(r.goal(), None);
+        assert_eq!(r.goal_path(), None);
+        assert_eq!(r.dist(1), Some(26));
+        assert_eq!(r.path_to(1).unwrap(), [6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        assert_eq!(r.dist(0), None);
+    }
+}
@@ -1,10 +1,12 @@
-use std::iter;
+use std::{fmt, iter, str::FromStr};
 
-use crate::usize_plus_i;
+use crate::{point::Point3d, usize_plus_i};
+
+use anyhow::Context;
 
 pub trait Direction: Sized + PartialEq + Copy {
-    /// Returns an iterator through all values of the `Direction`, starting with `Up` and
-    /// proceeding clockwise.
+    /// Returns an iterator through all values of the `Direction`, starting with `Up` (or the first
+    /// direction clockwise from it, if there is no `Up`) and proceeding clockwise.
     fn iter() -> impl Iterator<Item = Self>;
 
     /// Returns an iterator through all values of the `Direction`, starting with the given
@@ -88,6 +90,25 @@ pub trait Direction: Sized + PartialEq + Copy {
         })
     }
 
+    /// Similar to `iter_valid_usizes_deltas()`, but instead of skipping directions which would
+    /// leave the area described by `size`, wraps around to the opposite side (see
+    /// `apply_delta_to_usizes_wrapping()`). Note that for very small sizes, the results can
+    /// contain duplicates or `curr` itself.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Direction};
+    /// assert_eq!(
+    ///     Dir4::iter_wrapping_usizes_deltas((0, 5), (4, 6)).collect::<Vec<_>>(),
+    ///     [(0, 4), (1, 5), (0, 0), (3, 5)]
+    /// );
+    /// ```
+    fn iter_wrapping_usizes_deltas(
+        curr: (usize, usize),
+        size: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        Self::iter().map(move |d| d.apply_delta_to_usizes_wrapping(curr, size))
+    }
+
     /// Returns the `(x, y)` delta for one unit of the `Direction`.
     fn delta(self) -> (i8, i8);
 
@@ -97,12 +118,6 @@ pub trait Direction: Sized + PartialEq + Copy {
     /// Returns a new `Direction` which represents the given one being rotated "left" or counter-clockwise by one unit.
     fn rotate_left(self) -> Self;
 
-    /// Returns a new `Direction` which represents the given one being rotated "right" or clockwise by 90 degrees.
-    fn rotate_right_90(self) -> Self;
-
-    /// Returns a new `Direction` which represents the given one being rotated "left" or counter-clockwise by 90 degrees.
-    fn rotate_left_90(self) -> Self;
-
     /// Returns a new `Direction` which represents the "opposite" of the given one. Can also be
     /// seen as rotating the given one by 180 degrees.
     fn opposite(self) -> Self;
@@ -125,6 +140,152 @@ pub trait Direction: Sized + PartialEq + Copy {
             usize_plus_i(usizes.1, i64::from(d_y)),
         )
     }
+
+    /// Returns an iterator through the successive `(x, y)` coordinates reached by repeatedly
+    /// moving one unit in the given direction from `start` (not including `start` itself). Stops
+    /// when the next coordinate would leave the area described by `size`, rather than panicking.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Dir8, Direction};
+    /// assert_eq!(Dir4::Left.ray((2, 1), (4, 3)).collect::<Vec<_>>(), [(1, 1), (0, 1)]);
+    /// assert_eq!(Dir8::DownRight.ray((2, 1), (4, 3)).collect::<Vec<_>>(), [(3, 2)]);
+    /// assert_eq!(Dir4::Up.ray((2, 0), (4, 3)).count(), 0);
+    /// ```
+    fn ray(self, start: (usize, usize), size: (usize, usize)) -> Ray<Self> {
+        Ray {
+            dir: self,
+            curr: start,
+            size,
+            done: false,
+        }
+    }
+
+    /// Similar to `apply_delta_to_usizes()`, but treats the area described by `size` as a torus:
+    /// moving off one side comes back on the opposite side. Panics if either dimension of `size`
+    /// is `0`.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Dir8, Direction};
+    /// assert_eq!(Dir4::Left.apply_delta_to_usizes_wrapping((0, 0), (4, 6)), (3, 0));
+    /// assert_eq!(Dir8::DownRight.apply_delta_to_usizes_wrapping((3, 5), (4, 6)), (0, 0));
+    /// ```
+    fn apply_delta_to_usizes_wrapping(
+        self,
+        usizes: (usize, usize),
+        size: (usize, usize),
+    ) -> (usize, usize) {
+        self.apply_n_deltas_to_usizes_wrapping(usizes, 1, size)
+    }
+
+    /// Similar to `apply_delta_to_usizes_wrapping()`, but moves `n` units at once.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Dir8, Direction};
+    /// assert_eq!(Dir4::Right.apply_n_deltas_to_usizes_wrapping((1, 2), 10, (4, 6)), (3, 2));
+    /// assert_eq!(Dir8::UpLeft.apply_n_deltas_to_usizes_wrapping((1, 2), 3, (4, 6)), (2, 5));
+    /// ```
+    fn apply_n_deltas_to_usizes_wrapping(
+        self,
+        usizes: (usize, usize),
+        n: usize,
+        size: (usize, usize),
+    ) -> (usize, usize) {
+        let (d_x, d_y) = self.delta();
+        let wrap = |u: usize, d: i8, s: usize| {
+            let moved = i128::try_from(u).unwrap() + i128::from(d) * i128::try_from(n).unwrap();
+            usize::try_from(moved.rem_euclid(i128::try_from(s).unwrap())).unwrap()
+        };
+        (wrap(usizes.0, d_x, size.0), wrap(usizes.1, d_y, size.1))
+    }
+}
+
+/// A `Direction` which can be rotated by 90 degrees. Not all `Direction`s can (for example,
+/// hexagonal ones).
+pub trait Rotate90: Direction {
+    /// Returns a new `Direction` which represents the given one being rotated "right" or clockwise by 90 degrees.
+    fn rotate_right_90(self) -> Self;
+
+    /// Returns a new `Direction` which represents the given one being rotated "left" or counter-clockwise by 90 degrees.
+    fn rotate_left_90(self) -> Self;
+}
+
+/// Iterator through the cells along a straight line, returned by `Direction::ray()`.
+#[derive(Copy, Clone, Debug)]
+pub struct Ray<D> {
+    dir: D,
+    curr: (usize, usize),
+    size: (usize, usize),
+    done: bool,
+}
+
+impl<D: Direction> Ray<D> {
+    /// Returns the first coordinate along the `Ray` for which `predicate` returns `true`, or
+    /// `None` if the edge is reached first.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir8, Direction};
+    /// let seats = ["L.#", "...", "#.L"];
+    /// let is_seat = |(x, y): (usize, usize)| seats[y].as_bytes()[x] != b'.';
+    /// assert_eq!(Dir8::DownRight.ray((0, 0), (3, 3)).first_matching(is_seat), Some((2, 2)));
+    /// assert_eq!(Dir8::UpLeft.ray((0, 0), (3, 3)).first_matching(is_seat), None);
+    /// ```
+    pub fn first_matching<P>(mut self, mut predicate: P) -> Option<(usize, usize)>
+    where
+        P: FnMut((usize, usize)) -> bool,
+    {
+        self.find(|&pos| predicate(pos))
+    }
+
+    /// Counts the coordinates along the `Ray` up to and including the first one for which
+    /// `blocked` returns `true`, or up to the edge if none is blocked. This is the number of
+    /// cells "visible" from the start, since the blocking cell itself can be seen.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Direction};
+    /// let trees = [3, 0, 3, 7, 3];
+    /// let blocked = |(x, _): (usize, usize)| trees[x] >= trees[2];
+    /// assert_eq!(Dir4::Left.ray((2, 0), (5, 1)).count_until_blocked(blocked), 2);
+    /// assert_eq!(Dir4::Right.ray((2, 0), (5, 1)).count_until_blocked(blocked), 1);
+    /// assert_eq!(Dir4::Right.ray((4, 0), (5, 1)).count_until_blocked(blocked), 0);
+    /// ```
+    pub fn count_until_blocked<P>(self, mut blocked: P) -> usize
+    where
+        P: FnMut((usize, usize)) -> bool,
+    {
+        let mut count = 0;
+        for pos in self {
+            count += 1;
+            if blocked(pos) {
+                break;
+            }
+        }
+        count
+    }
+}
+
+impl<D: Direction> Iterator for Ray<D> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (dx, dy) = self.dir.delta();
+        let next = match (
+            self.curr.0.checked_add_signed(isize::from(dx)),
+            self.curr.1.checked_add_signed(isize::from(dy)),
+        ) {
+            (Some(x), Some(y)) if x < self.size.0 && y < self.size.1 => (x, y),
+            _ => {
+                self.done = true;
+                return None;
+            }
+        };
+
+        self.curr = next;
+        Some(next)
+    }
 }
 
 /// The four cardinal directions.
@@ -159,14 +320,6 @@ impl Direction for Dir4 {
         self.rotate_right().opposite()
     }
 
-    fn rotate_right_90(self) -> Dir4 {
-        self.rotate_right()
-    }
-
-    fn rotate_left_90(self) -> Dir4 {
-        self.rotate_left()
-    }
-
     fn opposite(self) -> Dir4 {
         match self {
             Dir4::Up => Dir4::Down,
@@ -181,6 +334,107 @@ impl Direction for Dir4 {
     }
 }
 
+impl Rotate90 for Dir4 {
+    fn rotate_right_90(self) -> Dir4 {
+        self.rotate_right()
+    }
+
+    fn rotate_left_90(self) -> Dir4 {
+        self.rotate_left()
+    }
+}
+
+impl Dir4 {
+    /// Parses a single character written in the given notation. `Letters` and `Compass` are
+    /// case-insensitive.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, DirNotation};
+    /// assert_eq!(Dir4::from_char_in('<', DirNotation::Arrows).unwrap(), Dir4::Left);
+    /// assert_eq!(Dir4::from_char_in('u', DirNotation::Letters).unwrap(), Dir4::Up);
+    /// assert_eq!(Dir4::from_char_in('E', DirNotation::Compass).unwrap(), Dir4::Right);
+    /// assert!(Dir4::from_char_in('E', DirNotation::Letters).is_err());
+    /// ```
+    pub fn from_char_in(c: char, notation: DirNotation) -> anyhow::Result<Dir4> {
+        let c = match notation {
+            DirNotation::Arrows => c,
+            DirNotation::Letters | DirNotation::Compass => c.to_ascii_uppercase(),
+        };
+        match Dir4::iter().find(|d| d.to_char_in(notation) == c) {
+            Some(d) => Ok(d),
+            None => anyhow::bail!("invalid {:?} direction: {:?}", notation, c),
+        }
+    }
+
+    /// Formats the `Dir4` as a single character in the given notation.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, DirNotation};
+    /// assert_eq!(Dir4::Down.to_char_in(DirNotation::Arrows), 'v');
+    /// assert_eq!(Dir4::Down.to_char_in(DirNotation::Letters), 'D');
+    /// assert_eq!(Dir4::Down.to_char_in(DirNotation::Compass), 'S');
+    /// ```
+    pub fn to_char_in(self, notation: DirNotation) -> char {
+        match (notation, self) {
+            (DirNotation::Arrows, Dir4::Up) => '^',
+            (DirNotation::Arrows, Dir4::Down) => 'v',
+            (DirNotation::Arrows, Dir4::Left) => '<',
+            (DirNotation::Arrows, Dir4::Right) => '>',
+            (DirNotation::Letters, Dir4::Up) => 'U',
+            (DirNotation::Letters, Dir4::Down) => 'D',
+            (DirNotation::Letters, Dir4::Left) => 'L',
+            (DirNotation::Letters, Dir4::Right) => 'R',
+            (DirNotation::Compass, Dir4::Up) => 'N',
+            (DirNotation::Compass, Dir4::Down) => 'S',
+            (DirNotation::Compass, Dir4::Left) => 'W',
+            (DirNotation::Compass, Dir4::Right) => 'E',
+        }
+    }
+}
+
+impl TryFrom<char> for Dir4 {
+    type Error = anyhow::Error;
+
+    /// Parses a single character written in any `DirNotation`.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::Dir4;
+    /// assert_eq!(Dir4::try_from('v').unwrap(), Dir4::Down);
+    /// assert_eq!(Dir4::try_from('L').unwrap(), Dir4::Left);
+    /// assert_eq!(Dir4::try_from('n').unwrap(), Dir4::Up);
+    /// assert!(Dir4::try_from('x').is_err());
+    /// ```
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match DirNotation::ALL
+            .iter()
+            .find_map(|&n| Dir4::from_char_in(c, n).ok())
+        {
+            Some(d) => Ok(d),
+            None => anyhow::bail!("invalid direction: {:?}", c),
+        }
+    }
+}
+
+impl FromStr for Dir4 {
+    type Err = anyhow::Error;
+
+    /// Parses a single-character string written in any `DirNotation`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Dir4::try_from(c),
+            _ => anyhow::bail!("invalid direction: {:?}", s),
+        }
+    }
+}
+
+impl fmt::Display for Dir4 {
+    /// Formats using `DirNotation::Arrows`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char_in(DirNotation::Arrows))
+    }
+}
+
 /// The four cardinal directions, plus the 4 diagonals in between.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Dir8 {
@@ -228,6 +482,22 @@ impl Direction for Dir8 {
         }
     }
 
+    fn opposite(self) -> Dir8 {
+        match self {
+            Dir8::Dir4(d4) => Dir8::Dir4(d4.opposite()),
+            Dir8::UpRight => Dir8::DownLeft,
+            Dir8::UpLeft => Dir8::DownRight,
+            Dir8::DownRight => Dir8::UpLeft,
+            Dir8::DownLeft => Dir8::UpRight,
+        }
+    }
+
+    fn iter() -> impl Iterator<Item = Dir8> {
+        Self::iter_from(Dir8::Dir4(Dir4::Up))
+    }
+}
+
+impl Rotate90 for Dir8 {
     fn rotate_right_90(self) -> Dir8 {
         match self {
             Dir8::Dir4(d4) => Dir8::Dir4(d4.rotate_right_90()),
@@ -247,18 +517,310 @@ impl Direction for Dir8 {
             Dir8::DownLeft => Dir8::DownRight,
         }
     }
+}
 
-    fn opposite(self) -> Dir8 {
+impl Dir8 {
+    /// Parses a string written in the given notation. `Letters` and `Compass` are
+    /// case-insensitive.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Dir8, DirNotation};
+    /// assert_eq!(Dir8::from_str_in("↗", DirNotation::Arrows).unwrap(), Dir8::UpRight);
+    /// assert_eq!(Dir8::from_str_in("dl", DirNotation::Letters).unwrap(), Dir8::DownLeft);
+    /// assert_eq!(Dir8::from_str_in("W", DirNotation::Compass).unwrap(), Dir8::Dir4(Dir4::Left));
+    /// assert!(Dir8::from_str_in("NE", DirNotation::Letters).is_err());
+    /// ```
+    pub fn from_str_in(s: &str, notation: DirNotation) -> anyhow::Result<Dir8> {
+        let s = match notation {
+            DirNotation::Arrows => s.to_owned(),
+            DirNotation::Letters | DirNotation::Compass => s.to_ascii_uppercase(),
+        };
+        match Dir8::iter().find(|d| d.to_str_in(notation) == s) {
+            Some(d) => Ok(d),
+            None => anyhow::bail!("invalid {:?} direction: {:?}", notation, s),
+        }
+    }
+
+    /// Formats the `Dir8` in the given notation.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Dir8, DirNotation};
+    /// assert_eq!(Dir8::DownRight.to_str_in(DirNotation::Arrows), "↘");
+    /// assert_eq!(Dir8::DownRight.to_str_in(DirNotation::Letters), "DR");
+    /// assert_eq!(Dir8::DownRight.to_str_in(DirNotation::Compass), "SE");
+    /// assert_eq!(Dir8::Dir4(Dir4::Up).to_str_in(DirNotation::Arrows), "^");
+    /// ```
+    pub fn to_str_in(self, notation: DirNotation) -> &'static str {
+        match (notation, self) {
+            (DirNotation::Arrows, Dir8::Dir4(Dir4::Up)) => "^",
+            (DirNotation::Arrows, Dir8::Dir4(Dir4::Down)) => "v",
+            (DirNotation::Arrows, Dir8::Dir4(Dir4::Left)) => "<",
+            (DirNotation::Arrows, Dir8::Dir4(Dir4::Right)) => ">",
+            (DirNotation::Arrows, Dir8::UpRight) => "↗",
+            (DirNotation::Arrows, Dir8::UpLeft) => "↖",
+            (DirNotation::Arrows, Dir8::DownRight) => "↘",
+            (DirNotation::Arrows, Dir8::DownLeft) => "↙",
+            (DirNotation::Letters, Dir8::Dir4(Dir4::Up)) => "U",
+            (DirNotation::Letters, Dir8::Dir4(Dir4::Down)) => "D",
+            (DirNotation::Letters, Dir8::Dir4(Dir4::Left)) => "L",
+            (DirNotation::Letters, Dir8::Dir4(Dir4::Right)) => "R",
+            (DirNotation::Letters, Dir8::UpRight) => "UR",
+            (DirNotation::Letters, Dir8::UpLeft) => "UL",
+            (DirNotation::Letters, Dir8::DownRight) => "DR",
+            (DirNotation::Letters, Dir8::DownLeft) => "DL",
+            (DirNotation::Compass, Dir8::Dir4(Dir4::Up)) => "N",
+            (DirNotation::Compass, Dir8::Dir4(Dir4::Down)) => "S",
+            (DirNotation::Compass, Dir8::Dir4(Dir4::Left)) => "W",
+            (DirNotation::Compass, Dir8::Dir4(Dir4::Right)) => "E",
+            (DirNotation::Compass, Dir8::UpRight) => "NE",
+            (DirNotation::Compass, Dir8::UpLeft) => "NW",
+            (DirNotation::Compass, Dir8::DownRight) => "SE",
+            (DirNotation::Compass, Dir8::DownLeft) => "SW",
+        }
+    }
+}
+
+impl TryFrom<char> for Dir8 {
+    type Error = anyhow::Error;
+
+    /// Parses a single character written in any `DirNotation`. Diagonals can only be written as a
+    /// single character with `DirNotation::Arrows`.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::{Dir4, Dir8};
+    /// assert_eq!(Dir8::try_from('↙').unwrap(), Dir8::DownLeft);
+    /// assert_eq!(Dir8::try_from('E').unwrap(), Dir8::Dir4(Dir4::Right));
+    /// ```
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        c.to_string().parse()
+    }
+}
+
+impl FromStr for Dir8 {
+    type Err = anyhow::Error;
+
+    /// Parses a string written in any `DirNotation`.
+    ///
+    /// ```
+    /// use aoclib_rs::dir::Dir8;
+    /// assert_eq!("NE".parse::<Dir8>().unwrap(), Dir8::UpRight);
+    /// assert_eq!("ul".parse::<Dir8>().unwrap(), Dir8::UpLeft);
+    /// assert!("NU".parse::<Dir8>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match DirNotation::ALL
+            .iter()
+            .find_map(|&n| Dir8::from_str_in(s, n).ok())
+        {
+            Some(d) => Ok(d),
+            None => anyhow::bail!("invalid direction: {:?}", s),
+        }
+    }
+}
+
+impl fmt::Display for Dir8 {
+    /// Formats using `DirNotation::Arrows`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_str_in(DirNotation::Arrows))
+    }
+}
+
+/// The ways puzzle inputs commonly write down directions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DirNotation {
+    /// `^`, `v`, `<`, `>`, plus `↗`, `↖`, `↘`, `↙` for diagonals.
+    Arrows,
+
+    /// `U`, `D`, `L`, `R`, plus `UR`, `UL`, `DR`, `DL` for diagonals.
+    Letters,
+
+    /// `N`, `S`, `W`, `E`, plus `NE`, `NW`, `SE`, `SW` for diagonals.
+    Compass,
+}
+
+impl DirNotation {
+    pub const ALL: [DirNotation; 3] = [
+        DirNotation::Arrows,
+        DirNotation::Letters,
+        DirNotation::Compass,
+    ];
+}
+
+/// Parses a string of moves like `"R8,U5,L5"` (with `delim` of `","`) or `"R 4"` into
+/// `(direction, count)` pairs. Each move is a direction character in any `DirNotation`, followed
+/// by the count, optionally separated by whitespace. Returns an error describing the first bad
+/// move, if any.
+///
+/// ```
+/// use aoclib_rs::dir::{Dir4, parse_moves};
+/// assert_eq!(
+///     parse_moves("R8,U5,L5,D3", ",").unwrap(),
+///     [(Dir4::Right, 8), (Dir4::Up, 5), (Dir4::Left, 5), (Dir4::Down, 3)]
+/// );
+/// assert_eq!(parse_moves("> 2", ",").unwrap(), [(Dir4::Right, 2)]);
+/// assert!(parse_moves("R8,X5", ",").is_err());
+/// assert!(parse_moves("R8,U", ",").is_err());
+/// ```
+pub fn parse_moves(s: &str, delim: &str) -> anyhow::Result<Vec<(Dir4, usize)>> {
+    let mut v = Vec::new();
+    for (i, token) in s.split(delim).enumerate() {
+        let token = token.trim();
+        let mut chars = token.chars();
+        let Some(c) = chars.next() else {
+            anyhow::bail!("move {}: empty", i + 1);
+        };
+        let context = || format!("move {} ({:?})", i + 1, token);
+        let d = Dir4::try_from(c).with_context(context)?;
+        let n = chars.as_str().trim().parse().with_context(context)?;
+        v.push((d, n));
+    }
+
+    Ok(v)
+}
+
+/// A direction in 3-dimensional space. Unlike `Direction`, there's no notion of rotation, since
+/// there's no single axis to rotate around.
+pub trait Direction3d: Sized + PartialEq + Copy {
+    /// Returns an iterator through all values of the `Direction3d`.
+    fn iter() -> impl Iterator<Item = Self>;
+
+    /// Returns the `(x, y, z)` delta for one unit of the `Direction3d`.
+    fn delta(self) -> (i8, i8, i8);
+
+    /// Returns a new `Direction3d` which represents the "opposite" of the given one.
+    fn opposite(self) -> Self;
+
+    /// Returns the result of "moving" `p` one unit in the given direction.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::{Dir6, Direction3d}, point::Point3d};
+    /// assert_eq!(Dir6::MinusZ.apply_delta(Point3d::new(1, 2, 3)), Point3d::new(1, 2, 2));
+    /// ```
+    fn apply_delta(self, p: Point3d<i64>) -> Point3d<i64> {
+        let (dx, dy, dz) = self.delta();
+        Point3d::new(
+            p.x() + i64::from(dx),
+            p.y() + i64::from(dy),
+            p.z() + i64::from(dz),
+        )
+    }
+
+    /// Iterates through the neighbours of `curr` in every direction.
+    fn iter_neighbours(curr: Point3d<i64>) -> impl Iterator<Item = Point3d<i64>> {
+        Self::iter().map(move |d| d.apply_delta(curr))
+    }
+
+    /// Similar to `iter_neighbours()`, but only returns neighbours inside the bounding box from
+    /// `min` to `max` (inclusive).
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::{Dir6, Dir26, Direction3d}, point::Point3d};
+    /// let (min, max) = (Point3d::new(0, 0, 0), Point3d::new(3, 3, 3));
+    /// assert_eq!(Dir6::iter_valid_neighbours(Point3d::new(0, 0, 0), min, max).count(), 3);
+    /// assert_eq!(Dir26::iter_valid_neighbours(Point3d::new(0, 0, 0), min, max).count(), 7);
+    /// assert_eq!(Dir26::iter_valid_neighbours(Point3d::new(1, 1, 1), min, max).count(), 26);
+    /// ```
+    fn iter_valid_neighbours(
+        curr: Point3d<i64>,
+        min: Point3d<i64>,
+        max: Point3d<i64>,
+    ) -> impl Iterator<Item = Point3d<i64>> {
+        Self::iter_neighbours(curr).filter(move |n| {
+            (min.x()..=max.x()).contains(&n.x())
+                && (min.y()..=max.y()).contains(&n.y())
+                && (min.z()..=max.z()).contains(&n.z())
+        })
+    }
+}
+
+/// The six directions towards the faces of a cube.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Dir6 {
+    PlusX,
+    MinusX,
+    PlusY,
+    MinusY,
+    PlusZ,
+    MinusZ,
+}
+
+impl Direction3d for Dir6 {
+    fn iter() -> impl Iterator<Item = Dir6> {
+        [
+            Dir6::PlusX,
+            Dir6::MinusX,
+            Dir6::PlusY,
+            Dir6::MinusY,
+            Dir6::PlusZ,
+            Dir6::MinusZ,
+        ]
+        .into_iter()
+    }
+
+    fn delta(self) -> (i8, i8, i8) {
         match self {
-            Dir8::Dir4(d4) => Dir8::Dir4(d4.opposite()),
-            Dir8::UpRight => Dir8::DownLeft,
-            Dir8::UpLeft => Dir8::DownRight,
-            Dir8::DownRight => Dir8::UpLeft,
-            Dir8::DownLeft => Dir8::UpRight,
+            Dir6::PlusX => (1, 0, 0),
+            Dir6::MinusX => (-1, 0, 0),
+            Dir6::PlusY => (0, 1, 0),
+            Dir6::MinusY => (0, -1, 0),
+            Dir6::PlusZ => (0, 0, 1),
+            Dir6::MinusZ => (0, 0, -1),
         }
     }
 
-    fn iter() -> impl Iterator<Item = Dir8> {
-        Self::iter_from(Dir8::Dir4(Dir4::Up))
+    fn opposite(self) -> Dir6 {
+        match self {
+            Dir6::PlusX => Dir6::MinusX,
+            Dir6::MinusX => Dir6::PlusX,
+            Dir6::PlusY => Dir6::MinusY,
+            Dir6::MinusY => Dir6::PlusY,
+            Dir6::PlusZ => Dir6::MinusZ,
+            Dir6::MinusZ => Dir6::PlusZ,
+        }
+    }
+}
+
+/// All 26 directions from the centre of a 3x3x3 cube to the other cubes: the 6 faces, 12 edges and
+/// 8 corners.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dir26 {
+    delta: (i8, i8, i8),
+}
+
+impl Dir26 {
+    /// Returns `None` unless each component is `-1`, `0` or `1`, and at least one is non-zero.
+    pub fn new(dx: i8, dy: i8, dz: i8) -> Option<Self> {
+        let valid = |d: i8| (-1..=1).contains(&d);
+        if !valid(dx) || !valid(dy) || !valid(dz) || (dx, dy, dz) == (0, 0, 0) {
+            return None;
+        }
+        Some(Self {
+            delta: (dx, dy, dz),
+        })
+    }
+}
+
+impl Direction3d for Dir26 {
+    fn iter() -> impl Iterator<Item = Dir26> {
+        (-1..=1).flat_map(|dz| {
+            (-1..=1).flat_map(move |dy| (-1..=1).filter_map(move |dx| Dir26::new(dx, dy, dz)))
+        })
+    }
+
+    fn delta(self) -> (i8, i8, i8) {
+        self.delta
+    }
+
+    fn opposite(self) -> Dir26 {
+        let (dx, dy, dz) = self.delta;
+        Self {
+            delta: (-dx, -dy, -dz),
+        }
+    }
+}
+
+impl From<Dir6> for Dir26 {
+    fn from(d: Dir6) -> Self {
+        Self { delta: d.delta() }
     }
 }
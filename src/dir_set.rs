@@ -0,0 +1,313 @@
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{BitAnd, BitOr, Not, Sub},
+};
+
+use crate::dir::{Dir4, Direction};
+
+/// A set of `Direction`s, stored as a bitset. Works with any `Direction` with at most 8 values
+/// (eg, `Dir4`, `Dir8` and the hex directions). Useful for describing which sides of a tile
+/// connect to its neighbours, as in pipe mazes.
+///
+/// ```
+/// use aoclib_rs::{dir::Dir4, dir_set::DirSet};
+/// let corner = DirSet::from_pipe_char('L').unwrap();
+/// assert!(corner.contains(Dir4::Up));
+/// assert!(corner.contains(Dir4::Right));
+/// assert_eq!(corner.rotate_right().to_pipe_char(), Some('F'));
+///
+/// // An `L` connects to a `-` on its right, since the `-` has a matching opening on its left.
+/// let horizontal = DirSet::from_pipe_char('-').unwrap();
+/// assert!(corner.connects(&horizontal, Dir4::Right));
+/// assert!(!horizontal.connects(&corner, Dir4::Right));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DirSet<D> {
+    bits: u8,
+    dir: PhantomData<D>,
+}
+
+impl<D: Direction> DirSet<D> {
+    /// Returns an empty `DirSet`.
+    pub const fn new() -> Self {
+        Self::from_bits(0)
+    }
+
+    /// Returns a `DirSet` containing every value of `D`.
+    pub fn all() -> Self {
+        D::iter().collect()
+    }
+
+    const fn from_bits(bits: u8) -> Self {
+        Self {
+            bits,
+            dir: PhantomData,
+        }
+    }
+
+    /// Can panic if `D` has more than 8 values.
+    fn bit(d: D) -> u8 {
+        let i = D::iter().position(|x| x == d).unwrap();
+        assert!(i < 8, "too many directions for a DirSet");
+        1 << i
+    }
+
+    /// Returns whether `d` was newly inserted.
+    pub fn insert(&mut self, d: D) -> bool {
+        let was_present = self.contains(d);
+        self.bits |= Self::bit(d);
+        !was_present
+    }
+
+    /// Returns whether `d` was present.
+    pub fn remove(&mut self, d: D) -> bool {
+        let was_present = self.contains(d);
+        self.bits &= !Self::bit(d);
+        was_present
+    }
+
+    pub fn contains(&self, d: D) -> bool {
+        self.bits & Self::bit(d) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        usize::try_from(self.bits.count_ones()).unwrap()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// Iterates through the `Direction`s in the set, in the order of `D::iter()`.
+    pub fn iter(&self) -> impl Iterator<Item = D> + use<D> {
+        let set = *self;
+        D::iter().filter(move |&d| set.contains(d))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_bits(self.bits | other.bits)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::from_bits(self.bits & other.bits)
+    }
+
+    /// Returns the `Direction`s in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self::from_bits(self.bits & !other.bits)
+    }
+
+    /// Returns every `Direction` not in `self`.
+    pub fn complement(&self) -> Self {
+        Self::all().difference(self)
+    }
+
+    /// Rotates every `Direction` in the set by one step clockwise (see `Direction::rotate_right()`).
+    pub fn rotate_right(&self) -> Self {
+        self.iter().map(|d| d.rotate_right()).collect()
+    }
+
+    /// Rotates every `Direction` in the set by one step counter-clockwise (see
+    /// `Direction::rotate_left()`).
+    pub fn rotate_left(&self) -> Self {
+        self.iter().map(|d| d.rotate_left()).collect()
+    }
+
+    /// Replaces every `Direction` in the set with its opposite.
+    pub fn opposite(&self) -> Self {
+        self.iter().map(|d| d.opposite()).collect()
+    }
+
+    /// Whether a tile with connections `self` connects to the neighbouring tile in direction `d`,
+    /// with connections `other`. This is the case if `self` contains `d` and `other` contains its
+    /// opposite.
+    pub fn connects(&self, other: &Self, d: D) -> bool {
+        self.contains(d) && other.contains(d.opposite())
+    }
+
+    /// Infers the connections of a hidden tile (such as one under a start marker) from its
+    /// neighbours: the result contains every `d` for which `neighbour(d)` returns a `DirSet`
+    /// containing `d.opposite()`. `neighbour` should return `None` where there is no neighbour.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::Dir4, dir_set::DirSet};
+    /// let n = |c| Some(DirSet::from_pipe_char(c).unwrap());
+    /// let start = DirSet::infer_from_neighbours(|d| match d {
+    ///     Dir4::Up => n('|'),
+    ///     Dir4::Right => n('F'),
+    ///     Dir4::Down => n('J'),
+    ///     Dir4::Left => None,
+    /// });
+    /// assert_eq!(start.to_pipe_char(), Some('|'));
+    /// ```
+    pub fn infer_from_neighbours<F>(mut neighbour: F) -> Self
+    where
+        F: FnMut(D) -> Option<Self>,
+    {
+        D::iter()
+            .filter(|&d| neighbour(d).is_some_and(|n| n.contains(d.opposite())))
+            .collect()
+    }
+
+    /// Same as `infer_from_neighbours()`, looking up neighbours of `pos` in 2-dimensional data
+    /// (interpreted as in `position_2d()`). Out of bounds neighbours are treated as absent.
+    pub fn infer_at(v: &[Vec<Self>], pos: (usize, usize)) -> Self {
+        Self::infer_from_neighbours(|d| {
+            let (dx, dy) = d.delta();
+            let x = pos.0.checked_add_signed(isize::from(dx))?;
+            let y = pos.1.checked_add_signed(isize::from(dy))?;
+            v.get(y)?.get(x).copied()
+        })
+    }
+}
+
+impl DirSet<Dir4> {
+    /// Parses a pipe maze tile: `|`, `-`, `L`, `J`, `7` and `F` connect two sides (`L` connects
+    /// up and right), and `.` connects nothing.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::Dir4, dir_set::DirSet};
+    /// let s = DirSet::from_pipe_char('7').unwrap();
+    /// assert_eq!(s.iter().collect::<Vec<_>>(), [Dir4::Down, Dir4::Left]);
+    /// assert!(DirSet::from_pipe_char('S').is_err());
+    /// ```
+    pub fn from_pipe_char(c: char) -> anyhow::Result<Self> {
+        Ok(match c {
+            '|' => [Dir4::Up, Dir4::Down].into_iter().collect(),
+            '-' => [Dir4::Left, Dir4::Right].into_iter().collect(),
+            'L' => [Dir4::Up, Dir4::Right].into_iter().collect(),
+            'J' => [Dir4::Up, Dir4::Left].into_iter().collect(),
+            '7' => [Dir4::Down, Dir4::Left].into_iter().collect(),
+            'F' => [Dir4::Down, Dir4::Right].into_iter().collect(),
+            '.' => Self::new(),
+            _ => anyhow::bail!("invalid pipe: {:?}", c),
+        })
+    }
+
+    /// The inverse of `from_pipe_char()`. Returns `None` if the set doesn't correspond to a pipe
+    /// maze tile (ie, it connects one, three or four sides).
+    pub fn to_pipe_char(&self) -> Option<char> {
+        "|-LJ7F."
+            .chars()
+            .find(|&c| Self::from_pipe_char(c).is_ok_and(|s| s == *self))
+    }
+}
+
+impl<D: Direction> Default for DirSet<D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D: Direction> From<D> for DirSet<D> {
+    fn from(d: D) -> Self {
+        Self::from_bits(Self::bit(d))
+    }
+}
+
+impl<D: Direction> FromIterator<D> for DirSet<D> {
+    fn from_iter<I: IntoIterator<Item = D>>(iter: I) -> Self {
+        let mut s = Self::new();
+        for d in iter {
+            s.insert(d);
+        }
+        s
+    }
+}
+
+impl<D: Direction> BitOr for DirSet<D> {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(&rhs)
+    }
+}
+
+impl<D: Direction> BitAnd for DirSet<D> {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(&rhs)
+    }
+}
+
+impl<D: Direction> Sub for DirSet<D> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(&rhs)
+    }
+}
+
+impl<D: Direction> Not for DirSet<D> {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        self.complement()
+    }
+}
+
+impl<D: Direction + fmt::Debug> fmt::Debug for DirSet<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dir::Dir8;
+
+    #[test]
+    fn test_set_operations() {
+        let a: DirSet<Dir4> = [Dir4::Up, Dir4::Left].into_iter().collect();
+        let b: DirSet<Dir4> = [Dir4::Up, Dir4::Right].into_iter().collect();
+        assert_eq!((a | b).len(), 3);
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), [Dir4::Up]);
+        assert_eq!((a - b).iter().collect::<Vec<_>>(), [Dir4::Left]);
+        assert_eq!(!a, [Dir4::Right, Dir4::Down].into_iter().collect());
+        assert_eq!(!DirSet::<Dir4>::all(), DirSet::new());
+        assert_eq!(a.opposite(), !a);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut s = DirSet::new();
+        assert!(s.insert(Dir8::UpLeft));
+        assert!(!s.insert(Dir8::UpLeft));
+        assert!(s.insert(Dir8::Dir4(Dir4::Down)));
+        assert_eq!(s.len(), 2);
+        assert_eq!(
+            s.rotate_left().iter().collect::<Vec<_>>(),
+            [Dir8::DownRight, Dir8::Dir4(Dir4::Left)]
+        );
+        assert!(s.remove(Dir8::UpLeft));
+        assert!(!s.remove(Dir8::UpLeft));
+        assert_eq!(s, DirSet::from(Dir8::Dir4(Dir4::Down)));
+        assert_eq!(DirSet::<Dir8>::all().len(), 8);
+    }
+
+    #[test]
+    fn test_pipe_chars_round_trip() {
+        for c in "|-LJ7F.".chars() {
+            assert_eq!(DirSet::from_pipe_char(c).unwrap().to_pipe_char(), Some(c));
+        }
+        assert_eq!(DirSet::<Dir4>::all().to_pipe_char(), None);
+    }
+
+    #[test]
+    fn test_infer_at() {
+        // Example from Advent of Code 2023 day 10, with the start marker at (0, 2).
+        let v: Vec<Vec<DirSet<Dir4>>> = ["..F7.", ".FJ|.", "SJ.L7", "|F--J", "LJ..."]
+            .iter()
+            .map(|l| {
+                l.chars()
+                    .map(|c| DirSet::from_pipe_char(c).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(DirSet::infer_at(&v, (0, 2)).to_pipe_char(), Some('F'));
+    }
+}
@@ -0,0 +1,317 @@
+use std::{collections::HashMap, hash::Hash, ops::Add};
+
+use crate::dijkstra::{Dijkstrable, PqElement};
+
+/// Storage for the distances computed by a search, ie, the `dist()`/`set_dist()` half of
+/// `Dijkstrable`. Combine with a `Neighbours` implementation using `SearchWithStore` to get a full
+/// `Dijkstrable`.
+pub trait DistStore<P, D> {
+    /// Returns the stored distance for `p`, or `None` if it has not been computed yet or is
+    /// impossible.
+    fn dist(&self, p: P) -> Option<D>;
+
+    fn set_dist(&mut self, p: P, d: Option<D>);
+}
+
+/// `DistStore` backed by a `Vec`, for searches over a fixed, dense set of points. Can be indexed
+/// by flat `usize` indices, or, if created with `new()`, by `(x, y)` positions in 2-dimensional
+/// data of a given size (interpreted as in `position_2d()`). Can panic if a point is out of range.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DenseDistStore<D> {
+    dists: Vec<Option<D>>,
+    /// `None` for stores created with `with_len()`, which can't be indexed by `(x, y)`.
+    width: Option<usize>,
+}
+
+impl<D: Copy> DenseDistStore<D> {
+    /// Creates an empty store for 2-dimensional data of size `(width, height)`.
+    pub fn new(size: (usize, usize)) -> Self {
+        Self {
+            dists: vec![None; size.0 * size.1],
+            width: Some(size.0),
+        }
+    }
+
+    /// Creates an empty store for `len` points indexed by `usize`. Indexing it by `(x, y)` will
+    /// panic.
+    pub fn with_len(len: usize) -> Self {
+        Self {
+            dists: vec![None; len],
+            width: None,
+        }
+    }
+
+    /// Number of points which can be stored.
+    pub fn len(&self) -> usize {
+        self.dists.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dists.is_empty()
+    }
+
+    /// All stored distances, by flat index.
+    pub fn dists(&self) -> &[Option<D>] {
+        &self.dists
+    }
+
+    /// Number of points with a distance.
+    pub fn reached(&self) -> usize {
+        self.dists.iter().filter(|d| d.is_some()).count()
+    }
+
+    fn index(&self, p: (usize, usize)) -> usize {
+        let width = self
+            .width
+            .expect("DenseDistStore created with with_len() can't be indexed by (x, y)");
+        assert!(p.0 < width);
+        p.1 * width + p.0
+    }
+}
+
+impl<D: Copy> DistStore<(usize, usize), D> for DenseDistStore<D> {
+    fn dist(&self, p: (usize, usize)) -> Option<D> {
+        self.dist(self.index(p))
+    }
+
+    fn set_dist(&mut self, p: (usize, usize), d: Option<D>) {
+        let i = self.index(p);
+        self.set_dist(i, d);
+    }
+}
+
+impl<D: Copy> DistStore<usize, D> for DenseDistStore<D> {
+    fn dist(&self, p: usize) -> Option<D> {
+        self.dists[p]
+    }
+
+    fn set_dist(&mut self, p: usize, d: Option<D>) {
+        self.dists[p] = d;
+    }
+}
+
+/// `DistStore` backed by a `HashMap`, for searches over points which are sparse, unbounded, or
+/// otherwise not easily indexed.
+#[derive(Clone, Debug)]
+pub struct SparseDistStore<P, D> {
+    dists: HashMap<P, D>,
+}
+
+impl<P, D> SparseDistStore<P, D> {
+    pub fn new() -> Self {
+        Self {
+            dists: HashMap::new(),
+        }
+    }
+
+    /// Distances to every point reached.
+    pub fn dists(&self) -> &HashMap<P, D> {
+        &self.dists
+    }
+
+    pub fn into_dists(self) -> HashMap<P, D> {
+        self.dists
+    }
+
+    /// Number of points with a distance.
+    pub fn reached(&self) -> usize {
+        self.dists.len()
+    }
+}
+
+impl<P, D> Default for SparseDistStore<P, D> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Hash + Eq, D: Copy> DistStore<P, D> for SparseDistStore<P, D> {
+    fn dist(&self, p: P) -> Option<D> {
+        self.dists.get(&p).copied()
+    }
+
+    fn set_dist(&mut self, p: P, d: Option<D>) {
+        match d {
+            Some(d) => self.dists.insert(p, d),
+            None => self.dists.remove(&p),
+        };
+    }
+}
+
+/// The neighbour-generating half of `Dijkstrable`. Combine with a `DistStore` using
+/// `SearchWithStore` to get a full `Dijkstrable`.
+pub trait Neighbours {
+    /// "Point", "node", or "vertex" type.
+    type Point: Copy;
+
+    /// Type for the distance.
+    type Dist: Copy + Add<Output = Self::Dist> + Ord;
+
+    /// Same as `Dijkstrable::neighbours()`, without the `Bounds`.
+    fn neighbours(&self, p: Self::Point) -> impl Iterator<Item = (Self::Point, Self::Dist)>;
+
+    /// Same as `Dijkstrable::is_impossible()`. The default implementation always returns `false`.
+    fn is_impossible(&self, _: Self::Point) -> bool {
+        false
+    }
+}
+
+impl<T: Neighbours> Neighbours for &T {
+    type Point = T::Point;
+    type Dist = T::Dist;
+
+    fn neighbours(&self, p: T::Point) -> impl Iterator<Item = (T::Point, T::Dist)> {
+        (**self).neighbours(p)
+    }
+
+    fn is_impossible(&self, p: T::Point) -> bool {
+        (**self).is_impossible(p)
+    }
+}
+
+/// A `Dijkstrable` made out of a `Neighbours` implementation and a `DistStore`. The `Bounds` is
+/// `()`.
+///
+/// ```
+/// use aoclib_rs::{
+///     dijkstra::Dijkstrable,
+///     dir::{Dir4, Direction},
+///     dist_store::{DenseDistStore, DistStore, Neighbours, SearchWithStore},
+/// };
+///
+/// struct Maze(Vec<&'static str>);
+///
+/// impl Neighbours for Maze {
+///     type Point = (usize, usize);
+///     type Dist = u32;
+///
+///     fn neighbours(&self, p: (usize, usize)) -> impl Iterator<Item = ((usize, usize), u32)> {
+///         let size = (self.0[0].len(), self.0.len());
+///         Dir4::iter_valid_usizes_deltas(p, size)
+///             .filter(|n| self.0[n.1].as_bytes()[n.0] == b'.')
+///             .map(|n| (n, 1))
+///     }
+/// }
+///
+/// let mut s = SearchWithStore::new(
+///     Maze(vec!["..#", "#..", "..."]),
+///     DenseDistStore::new((3, 3)),
+/// );
+/// s.bfs((0, 0), 0, ());
+/// assert_eq!(s.store().dist((0, 2)), Some(4));
+/// assert_eq!(s.store().reached(), 7);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SearchWithStore<N, S> {
+    neighbours: N,
+    store: S,
+}
+
+impl<N, S> SearchWithStore<N, S> {
+    pub fn new(neighbours: N, store: S) -> Self {
+        Self { neighbours, store }
+    }
+
+    pub fn neighbours(&self) -> &N {
+        &self.neighbours
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn into_store(self) -> S {
+        self.store
+    }
+}
+
+impl<N, S> Dijkstrable for SearchWithStore<N, S>
+where
+    N: Neighbours,
+    S: DistStore<N::Point, N::Dist>,
+{
+    type Point = N::Point;
+    type Bounds = ();
+    type Dist = N::Dist;
+    type PQE = PqElement<N::Point, N::Dist>;
+
+    fn neighbours(&self, p: N::Point, _: ()) -> impl Iterator<Item = (N::Point, N::Dist)> {
+        self.neighbours.neighbours(p)
+    }
+
+    fn is_impossible(&self, p: N::Point) -> bool {
+        self.neighbours.is_impossible(p)
+    }
+
+    fn dist(&self, p: N::Point) -> Option<N::Dist> {
+        self.store.dist(p)
+    }
+
+    fn set_dist(&mut self, p: N::Point, d: Option<N::Dist>) {
+        self.store.set_dist(p, d);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Directed graph given as adjacency lists, indexed by node.
+    struct Graph(Vec<Vec<(usize, u64)>>);
+
+    impl Neighbours for Graph {
+        type Point = usize;
+        type Dist = u64;
+
+        fn neighbours(&self, p: usize) -> impl Iterator<Item = (usize, u64)> {
+            self.0[p].iter().copied()
+        }
+    }
+
+    fn graph() -> Graph {
+        Graph(vec![
+            vec![(1, 7), (2, 2)],
+            vec![(3, 1)],
+            vec![(1, 3), (3, 8)],
+            vec![],
+            vec![(0, 1)],
+        ])
+    }
+
+    #[test]
+    fn test_dense_flat_index() {
+        let mut s = SearchWithStore::new(graph(), DenseDistStore::with_len(5));
+        s.dijkstra(0, 0, ());
+        assert_eq!(
+            s.store().dists(),
+            [Some(0), Some(5), Some(2), Some(6), None]
+        );
+        assert_eq!(s.store().reached(), 4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_dense_x_out_of_range() {
+        let mut s = DenseDistStore::new((3, 3));
+        s.set_dist((4, 0), Some(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_flat_not_2d() {
+        let s = DenseDistStore::<u32>::with_len(9);
+        s.dist((1, 1));
+    }
+
+    #[test]
+    fn test_sparse_matches_dense() {
+        let mut dense = SearchWithStore::new(graph(), DenseDistStore::with_len(5));
+        dense.dijkstra_multi([(4, 0), (2, 0)], ());
+        let mut sparse = SearchWithStore::new(graph(), SparseDistStore::new());
+        sparse.dijkstra_multi([(4, 0), (2, 0)], ());
+        for p in 0..5 {
+            assert_eq!(dense.store().dist(p), sparse.store().dist(p));
+        }
+        assert_eq!(sparse.into_store().into_dists().len(), 5);
+    }
+}
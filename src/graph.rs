@@ -0,0 +1,380 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash, ops::Add};
+
+use crate::dist_store::{DenseDistStore, Neighbours, SearchWithStore};
+
+use anyhow::Context;
+use num_traits::One;
+
+/// Maps node names to dense `usize` ids (assigned in order of first appearance) and back.
+///
+/// ```
+/// use aoclib_rs::graph::Interner;
+/// let mut i = Interner::new();
+/// assert_eq!(i.intern("kh"), 0);
+/// assert_eq!(i.intern("tc"), 1);
+/// assert_eq!(i.intern("kh"), 0);
+/// assert_eq!(i.name(1), &"tc");
+/// assert_eq!(i.get("qp"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Interner<N> {
+    ids: HashMap<N, usize>,
+    names: Vec<N>,
+}
+
+impl<N: Clone + Hash + Eq> Interner<N> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Returns the id of `name`, assigning the next unused id if it hasn't been seen before.
+    pub fn intern(&mut self, name: N) -> usize {
+        if let Some(&id) = self.ids.get(&name) {
+            return id;
+        }
+
+        let id = self.names.len();
+        self.ids.insert(name.clone(), id);
+        self.names.push(name);
+        id
+    }
+
+    /// Returns the id of `name`, or `None` if it hasn't been interned.
+    pub fn get<Q>(&self, name: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Eq,
+        N: Borrow<Q>,
+    {
+        self.ids.get(name).copied()
+    }
+
+    /// Can panic if `id` is out of range.
+    pub fn name(&self, id: usize) -> &N {
+        &self.names[id]
+    }
+
+    /// Number of names interned.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Iterates through `(id, name)` pairs, in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &N)> {
+        self.names.iter().enumerate()
+    }
+}
+
+impl<N: Clone + Hash + Eq> Default for Interner<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A graph stored as adjacency lists, with nodes named by `N` (interned to `usize` ids) and edges
+/// weighted by `E`. Either directed or undirected; an undirected edge is stored as an edge in
+/// each direction.
+///
+/// Implements `Neighbours` over node ids, so can be searched with any `Dijkstrable` method via
+/// `searcher()`.
+///
+/// ```
+/// use aoclib_rs::{dijkstra::Dijkstrable, graph::Graph};
+/// let g = Graph::<String, u32>::parse_adjacency(
+///     &["aa -> bb, cc", "bb -> dd", "cc -> dd", "dd -> "],
+///     " -> ",
+///     ", ",
+///     true,
+/// )
+/// .unwrap();
+/// let (aa, dd) = (g.node_id("aa").unwrap(), g.node_id("dd").unwrap());
+/// assert_eq!(g.in_degree(dd), 2);
+/// let mut s = g.searcher();
+/// s.dijkstra(aa, 0, ());
+/// assert_eq!(s.store().dists()[dd], Some(2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Graph<N, E> {
+    nodes: Interner<N>,
+    edges: Vec<Vec<(usize, E)>>,
+    in_degrees: Vec<usize>,
+    directed: bool,
+}
+
+impl<N, E> Graph<N, E>
+where
+    N: Clone + Hash + Eq,
+    E: Copy,
+{
+    pub fn new_directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn new_undirected() -> Self {
+        Self::new(false)
+    }
+
+    fn new(directed: bool) -> Self {
+        Self {
+            nodes: Interner::new(),
+            edges: Vec::new(),
+            in_degrees: Vec::new(),
+            directed,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the id of the node named `name`, adding it if it doesn't exist yet.
+    pub fn add_node(&mut self, name: N) -> usize {
+        let id = self.nodes.intern(name);
+        if id == self.edges.len() {
+            self.edges.push(Vec::new());
+            self.in_degrees.push(0);
+        }
+        id
+    }
+
+    /// Adds an edge from `a` to `b` (and from `b` to `a`, if undirected), adding the nodes if they
+    /// don't exist yet. Returns the ids of `a` and `b`.
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> (usize, usize) {
+        let (a, b) = (self.add_node(a), self.add_node(b));
+        self.add_edge_ids(a, b, weight);
+        (a, b)
+    }
+
+    /// Same as `add_edge()`, for nodes which already exist. An undirected edge from a node to
+    /// itself is only stored once. Can panic if either id is out of range.
+    pub fn add_edge_ids(&mut self, a: usize, b: usize, weight: E) {
+        self.edges[a].push((b, weight));
+        self.in_degrees[b] += 1;
+        if !self.directed && a != b {
+            self.edges[b].push((a, weight));
+            self.in_degrees[a] += 1;
+        }
+    }
+
+    /// Returns the id of the node named `name`, or `None` if there's no such node.
+    pub fn node_id<Q>(&self, name: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Eq,
+        N: Borrow<Q>,
+    {
+        self.nodes.get(name)
+    }
+
+    /// Can panic if `id` is out of range.
+    pub fn name(&self, id: usize) -> &N {
+        self.nodes.name(id)
+    }
+
+    pub fn interner(&self) -> &Interner<N> {
+        &self.nodes
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Number of edges. Each undirected edge is counted once.
+    pub fn edge_count(&self) -> usize {
+        self.edges().count()
+    }
+
+    /// Iterates through `(id, name)` pairs for every node, in id order.
+    pub fn nodes(&self) -> impl Iterator<Item = (usize, &N)> {
+        self.nodes.iter()
+    }
+
+    /// Iterates through every edge as `(from, to, weight)`. Each undirected edge is yielded once,
+    /// with `from <= to`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, E)> {
+        self.edges.iter().enumerate().flat_map(move |(a, e)| {
+            e.iter()
+                .filter(move |&&(b, _)| self.directed || a <= b)
+                .map(move |&(b, w)| (a, b, w))
+        })
+    }
+
+    /// Every `(to, weight)` edge leaving `id`. Can panic if `id` is out of range.
+    pub fn out_edges(&self, id: usize) -> &[(usize, E)] {
+        &self.edges[id]
+    }
+
+    /// Ids of every node adjacent to `id` (following edge direction, if directed). Can panic if
+    /// `id` is out of range.
+    pub fn adjacent(&self, id: usize) -> impl Iterator<Item = usize> {
+        self.edges[id].iter().map(|&(b, _)| b)
+    }
+
+    /// Number of edges leaving `id`. Can panic if `id` is out of range.
+    pub fn out_degree(&self, id: usize) -> usize {
+        self.edges[id].len()
+    }
+
+    /// Number of edges arriving at `id`. For undirected graphs, this is the same as
+    /// `out_degree()`. Can panic if `id` is out of range.
+    pub fn in_degree(&self, id: usize) -> usize {
+        self.in_degrees[id]
+    }
+
+    /// Number of edges touching `id`: the same as `out_degree()` for undirected graphs, or the sum
+    /// of `in_degree()` and `out_degree()` for directed graphs. Can panic if `id` is out of range.
+    pub fn degree(&self, id: usize) -> usize {
+        if self.directed {
+            self.in_degree(id) + self.out_degree(id)
+        } else {
+            self.out_degree(id)
+        }
+    }
+
+    /// Returns a `Dijkstrable` over the node ids of this graph, with a fresh `DenseDistStore` (so
+    /// distances from earlier searches never leak into a new one).
+    pub fn searcher(&self) -> SearchWithStore<&Self, DenseDistStore<E>> {
+        SearchWithStore::new(self, DenseDistStore::with_len(self.len()))
+    }
+}
+
+impl<E: Copy + One> Graph<String, E> {
+    /// Parses lines each describing one edge of weight 1 as two node names separated by `delim`,
+    /// like `"kh-tc"`. Returns an error describing the first bad line, if any.
+    ///
+    /// ```
+    /// use aoclib_rs::graph::Graph;
+    /// let g = Graph::<String, u32>::parse_pairs(&["kh-tc", "qp-kh", "de-cg"], "-", false).unwrap();
+    /// assert_eq!(g.len(), 5);
+    /// assert_eq!(g.degree(g.node_id("kh").unwrap()), 2);
+    /// assert!(Graph::<String, u32>::parse_pairs(&["kh-tc", "qp"], "-", false).is_err());
+    /// ```
+    pub fn parse_pairs(lines: &[&str], delim: &str, directed: bool) -> anyhow::Result<Self> {
+        let mut g = Self::new(directed);
+        for (i, line) in lines.iter().enumerate() {
+            let (a, b) = line
+                .split_once(delim)
+                .with_context(|| format!("line {}: missing {:?} in {:?}", i + 1, delim, line))?;
+            g.add_edge(a.trim().to_string(), b.trim().to_string(), E::one());
+        }
+        Ok(g)
+    }
+
+    /// Parses lines each describing edges of weight 1 from one node to a list of others, like
+    /// `"aa -> bb, cc"` (with `sep` of `" -> "` and `list_delim` of `", "`) or `"aa: bb cc"` (with
+    /// `sep` of `":"` and `list_delim` of `" "`). Every node named on the left of `sep` is added,
+    /// even if its list is empty. Returns an error describing the first bad line, if any.
+    pub fn parse_adjacency(
+        lines: &[&str],
+        sep: &str,
+        list_delim: &str,
+        directed: bool,
+    ) -> anyhow::Result<Self> {
+        let mut g = Self::new(directed);
+        for (i, line) in lines.iter().enumerate() {
+            let (from, to) = line
+                .split_once(sep)
+                .with_context(|| format!("line {}: missing {:?} in {:?}", i + 1, sep, line))?;
+            let from = g.add_node(from.trim().to_string());
+            for name in to
+                .split(list_delim)
+                .map(str::trim)
+                .filter(|n| !n.is_empty())
+            {
+                let to = g.add_node(name.to_string());
+                g.add_edge_ids(from, to, E::one());
+            }
+        }
+        Ok(g)
+    }
+}
+
+impl<N, E> Neighbours for Graph<N, E>
+where
+    E: Copy + Add<Output = E> + Ord,
+{
+    type Point = usize;
+    type Dist = E;
+
+    fn neighbours(&self, p: usize) -> impl Iterator<Item = (usize, E)> {
+        self.edges[p].iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dijkstra::Dijkstrable;
+
+    #[test]
+    fn test_undirected_edges() {
+        let mut g = Graph::new_undirected();
+        g.add_edge("a", "b", 3);
+        g.add_edge("b", "c", 4);
+        g.add_edge("c", "c", 1);
+        assert_eq!(g.edge_count(), 3);
+        assert_eq!(
+            g.edges().collect::<Vec<_>>(),
+            [(0, 1, 3), (1, 2, 4), (2, 2, 1)]
+        );
+        assert_eq!(g.degree(1), 2);
+        assert_eq!(g.in_degree(1), 2);
+        assert_eq!(g.adjacent(1).collect::<Vec<_>>(), [0, 2]);
+    }
+
+    #[test]
+    fn test_directed_degrees() {
+        let g = Graph::<String, u32>::parse_adjacency(
+            &[
+                "aaa: you hhh",
+                "you: bbb ccc",
+                "bbb: ddd eee",
+                "ccc: ddd eee fff",
+            ],
+            ":",
+            " ",
+            true,
+        )
+        .unwrap();
+        assert_eq!(g.len(), 8);
+        assert_eq!(g.edge_count(), 9);
+        let ddd = g.node_id("ddd").unwrap();
+        assert_eq!(
+            (g.in_degree(ddd), g.out_degree(ddd), g.degree(ddd)),
+            (2, 0, 2)
+        );
+        assert_eq!(g.name(ddd), "ddd");
+        assert!(Graph::<String, u32>::parse_adjacency(&["aaa you"], ":", " ", true).is_err());
+    }
+
+    #[test]
+    fn test_repeated_searches() {
+        let g = Graph::<String, u64>::parse_pairs(&["a-b", "b-c", "d-e"], "-", false).unwrap();
+        let (a, b) = (g.node_id("a").unwrap(), g.node_id("b").unwrap());
+        let mut s = g.searcher();
+        s.dijkstra(a, 0, ());
+        assert_eq!(s.store().dists(), [Some(0), Some(1), Some(2), None, None]);
+
+        let mut s = g.searcher();
+        s.dijkstra(b, 0, ());
+        assert_eq!(s.store().dists(), [Some(1), Some(0), Some(1), None, None]);
+
+        let e = g.node_id("e").unwrap();
+        let mut s = g.searcher();
+        let preds = s.dijkstra_with_predecessors(e, 0, ());
+        assert_eq!(s.dist(g.node_id("d").unwrap()), Some(1));
+        assert_eq!(s.dist(a), None);
+        assert_eq!(preds.path_to(a), None);
+    }
+}
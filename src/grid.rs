@@ -0,0 +1,575 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    fmt::Formatter,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    dir::Direction,
+    pad_vec,
+    point::Point2d,
+    position_2d,
+    region::{self, Labelling, Region},
+    transform::{self, Orientation},
+};
+
+use anyhow::Context;
+
+/// A position which can be used to index into a `Grid`.
+pub trait GridPos {
+    /// Returns the `(x, y)` indices represented by `self`, or `None` if they can't be represented
+    /// as `usize`s (for example, negative coordinates).
+    fn to_usizes(&self) -> Option<(usize, usize)>;
+}
+
+impl GridPos for (usize, usize) {
+    fn to_usizes(&self) -> Option<(usize, usize)> {
+        Some(*self)
+    }
+}
+
+impl<T> GridPos for Point2d<T>
+where
+    T: Copy + TryInto<usize>,
+{
+    fn to_usizes(&self) -> Option<(usize, usize)> {
+        Some((self.x().try_into().ok()?, self.y().try_into().ok()?))
+    }
+}
+
+impl<P: GridPos> GridPos for &P {
+    fn to_usizes(&self) -> Option<(usize, usize)> {
+        (*self).to_usizes()
+    }
+}
+
+/// Rectangular 2-dimensional data. Interpreted the same way as `position_2d()`:
+///
+/// `vec![vec![1, 2, 3], vec![4, 5, 6]]` is
+///
+/// 1 2 3
+/// 4 5 6
+///
+/// That is, the cell containing `3` is at `(2, 0)` - in `(x, y)` form.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<Vec<T>>,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    /// Creates a `Grid` from rows of cells. Returns an error if the rows are not all the same
+    /// length.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// assert!(Grid::new(vec![vec![1, 2], vec![3, 4]]).is_ok());
+    /// assert!(Grid::new(vec![vec![1, 2], vec![3]]).is_err());
+    /// ```
+    pub fn new(cells: Vec<Vec<T>>) -> anyhow::Result<Self> {
+        let width = cells.first().map_or(0, |row| row.len());
+        for (y, row) in cells.iter().enumerate() {
+            if row.len() != width {
+                anyhow::bail!(
+                    "ragged grid: row {} has width {} but row 0 has width {}",
+                    y,
+                    row.len(),
+                    width
+                );
+            }
+        }
+
+        Ok(Self { cells, width })
+    }
+
+    /// Creates a `width` x `height` `Grid` where each cell is initialized by calling `f` with its
+    /// `(x, y)` position.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::from_fn(3, 2, |(x, y)| x + y * 3);
+    /// assert_eq!(g[(2, 1)], 5);
+    /// ```
+    pub fn from_fn<F>(width: usize, height: usize, mut f: F) -> Self
+    where
+        F: FnMut((usize, usize)) -> T,
+    {
+        let cells = (0..height)
+            .map(|y| (0..width).map(|x| f((x, y))).collect())
+            .collect();
+        Self { cells, width }
+    }
+
+    /// Parses lines of characters (as returned by `prep_io()`) into a `Grid`, converting each
+    /// character into a cell with `f`. Returns an error if the lines are not all the same width.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::parse(&["#.", ".#"], |c| c == '#').unwrap();
+    /// assert_eq!(g.rows(), [vec![true, false], vec![false, true]]);
+    /// assert!(Grid::parse(&["#.", "."], |c| c == '#').is_err());
+    /// ```
+    pub fn parse<F>(lines: &[&str], mut f: F) -> anyhow::Result<Self>
+    where
+        F: FnMut(char) -> T,
+    {
+        Self::try_parse(lines, |c| Ok(f(c)))
+    }
+
+    /// Like `parse()`, but `f` can fail. Errors from `f` are reported along with the line and
+    /// column (both 1-based) of the offending character.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::try_parse(&["12", "34"], |c| {
+    ///     c.to_digit(10).ok_or_else(|| anyhow::anyhow!("not a digit: {}", c))
+    /// });
+    /// assert_eq!(g.unwrap().rows(), [vec![1, 2], vec![3, 4]]);
+    ///
+    /// let g = Grid::try_parse(&["12", "3x"], |c| {
+    ///     c.to_digit(10).ok_or_else(|| anyhow::anyhow!("not a digit: {}", c))
+    /// });
+    /// assert_eq!(g.unwrap_err().to_string(), "line 2, column 2");
+    /// ```
+    pub fn try_parse<F>(lines: &[&str], f: F) -> anyhow::Result<Self>
+    where
+        F: FnMut(char) -> anyhow::Result<T>,
+    {
+        Ok(Self::try_parse_with_markers(lines, &[], f)?.0)
+    }
+
+    /// Like `parse()`, but also records the positions of every occurrence of each character in
+    /// `markers` (for example, the start and end points of a maze). Marker characters are still
+    /// converted into cells with `f`.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let (g, markers) =
+    ///     Grid::parse_with_markers(&["S.#", "..E"], &['S', 'E'], |c| c == '#').unwrap();
+    /// assert_eq!(g[(2, 0)], true);
+    /// assert_eq!(markers.get('S'), Some((0, 0)));
+    /// assert_eq!(markers.get('E'), Some((2, 1)));
+    /// ```
+    pub fn parse_with_markers<F>(
+        lines: &[&str],
+        markers: &[char],
+        mut f: F,
+    ) -> anyhow::Result<(Self, Markers)>
+    where
+        F: FnMut(char) -> T,
+    {
+        Self::try_parse_with_markers(lines, markers, |c| Ok(f(c)))
+    }
+
+    /// Combination of `try_parse()` and `parse_with_markers()`.
+    pub fn try_parse_with_markers<F>(
+        lines: &[&str],
+        markers: &[char],
+        mut f: F,
+    ) -> anyhow::Result<(Self, Markers)>
+    where
+        F: FnMut(char) -> anyhow::Result<T>,
+    {
+        let mut found = Markers::default();
+        for &m in markers {
+            found.0.insert(m, Vec::new());
+        }
+
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut cells = Vec::with_capacity(lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            let mut row = Vec::with_capacity(width);
+            for (x, c) in line.chars().enumerate() {
+                if let Some(positions) = found.0.get_mut(&c) {
+                    positions.push((x, y));
+                }
+                row.push(f(c).with_context(|| format!("line {}, column {}", y + 1, x + 1))?);
+            }
+
+            if row.len() != width {
+                anyhow::bail!(
+                    "ragged grid: line {} has width {} but line 1 has width {}",
+                    y + 1,
+                    row.len(),
+                    width
+                );
+            }
+            cells.push(row);
+        }
+
+        Ok((Self { cells, width }, found))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `(width, height)`, suitable for passing as the `size` argument of
+    /// `Direction::iter_valid_usizes_deltas()`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+
+    /// Whether `pos` lies within the `Grid`.
+    pub fn in_bounds<P: GridPos>(&self, pos: P) -> bool {
+        self.get(pos).is_some()
+    }
+
+    /// Returns the cell at `pos`, or `None` if it's out of bounds.
+    ///
+    /// ```
+    /// use aoclib_rs::{grid::Grid, point::Point2d};
+    /// let g = Grid::new(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// assert_eq!(g.get((2, 0)), Some(&3));
+    /// assert_eq!(g.get(Point2d::new(1i64, 1)), Some(&5));
+    /// assert_eq!(g.get(Point2d::new(-1i64, 1)), None);
+    /// assert_eq!(g.get((3, 0)), None);
+    /// ```
+    pub fn get<P: GridPos>(&self, pos: P) -> Option<&T> {
+        let (x, y) = pos.to_usizes()?;
+        self.cells.get(y)?.get(x)
+    }
+
+    /// Returns the cell at `pos` mutably, or `None` if it's out of bounds.
+    pub fn get_mut<P: GridPos>(&mut self, pos: P) -> Option<&mut T> {
+        let (x, y) = pos.to_usizes()?;
+        self.cells.get_mut(y)?.get_mut(x)
+    }
+
+    /// Returns the underlying rows.
+    pub fn rows(&self) -> &[Vec<T>] {
+        &self.cells
+    }
+
+    /// Consumes the `Grid`, returning the underlying rows.
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        self.cells
+    }
+
+    /// Iterates through every `(x, y)` position in the `Grid`, left-to-right, top-to-bottom.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let (width, height) = self.size();
+        (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterates through every cell along with its `(x, y)` position, left-to-right,
+    /// top-to-bottom.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| ((x, y), c)))
+    }
+
+    /// Returns the position of the first cell (left-to-right, top-to-bottom) for which
+    /// `predicate` returns `true`. See `position_2d()`.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::new(vec![vec!['.', 'S'], vec!['.', '.']]).unwrap();
+    /// assert_eq!(g.find(|&c| c == 'S'), Some((1, 0)));
+    /// ```
+    pub fn find<P>(&self, predicate: P) -> Option<(usize, usize)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        position_2d(&self.cells, predicate)
+    }
+
+    /// Returns the positions of all cells (left-to-right, top-to-bottom) for which `predicate`
+    /// returns `true`.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::new(vec![vec!['#', '.'], vec!['.', '#']]).unwrap();
+    /// assert_eq!(g.find_all(|&c| c == '#').collect::<Vec<_>>(), [(0, 0), (1, 1)]);
+    /// ```
+    pub fn find_all<P>(&self, mut predicate: P) -> impl Iterator<Item = (usize, usize)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.iter()
+            .filter_map(move |(pos, c)| if predicate(c) { Some(pos) } else { None })
+    }
+
+    /// Iterates through the in-bounds neighbours of `pos`, using the directions of `D`. See
+    /// `Direction::iter_valid_usizes_deltas()`.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::Dir4, grid::Grid};
+    /// let g = Grid::new(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// assert_eq!(g.neighbours::<Dir4>((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+    /// ```
+    pub fn neighbours<D: Direction>(
+        &self,
+        pos: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + use<D, T> {
+        D::iter_valid_usizes_deltas(pos, self.size())
+    }
+
+    /// See `region::flood_fill()`.
+    pub fn flood_fill<D, P>(&self, start: (usize, usize), predicate: P) -> Region
+    where
+        D: Direction,
+        P: FnMut(&T) -> bool,
+    {
+        region::flood_fill::<D, _, _>(&self.cells, start, predicate)
+    }
+
+    /// See `region::label_components()`.
+    pub fn label_components<D, I, C>(&self, include: I, connected: C) -> Labelling
+    where
+        D: Direction,
+        I: FnMut(&T) -> bool,
+        C: FnMut(&T, &T) -> bool,
+    {
+        region::label_components::<D, _, _, _>(&self.cells, include, connected)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a `width` x `height` `Grid` with every cell set to `val`.
+    pub fn filled(width: usize, height: usize, val: T) -> Self {
+        Self {
+            cells: vec![vec![val; width]; height],
+            width,
+        }
+    }
+
+    /// See `transform::transpose()`.
+    pub fn transpose(&self) -> Self {
+        Self::from_transformed(transform::transpose(&self.cells), self.swapped_size())
+    }
+
+    /// See `transform::rotate_right()`.
+    pub fn rotate_right(&self) -> Self {
+        Self::from_transformed(transform::rotate_right(&self.cells), self.swapped_size())
+    }
+
+    /// See `transform::rotate_left()`.
+    pub fn rotate_left(&self) -> Self {
+        Self::from_transformed(transform::rotate_left(&self.cells), self.swapped_size())
+    }
+
+    /// See `transform::rotate_180()`.
+    pub fn rotate_180(&self) -> Self {
+        Self::from_transformed(transform::rotate_180(&self.cells), self.size())
+    }
+
+    /// See `transform::flip_horizontal()`.
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_transformed(transform::flip_horizontal(&self.cells), self.size())
+    }
+
+    /// See `transform::flip_vertical()`.
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_transformed(transform::flip_vertical(&self.cells), self.size())
+    }
+
+    /// Returns a new `Grid` with `o` applied. See `Orientation::apply()`.
+    pub fn orient(&self, o: Orientation) -> Self {
+        let size = if o.rotations().is_multiple_of(2) {
+            self.size()
+        } else {
+            self.swapped_size()
+        };
+        Self::from_transformed(o.apply(&self.cells), size)
+    }
+
+    /// Iterates through all 8 orientations of the `Grid`, in the order of `Orientation::iter()`.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::new(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+    /// for (o, oriented) in g.orientations() {
+    ///     assert_eq!(oriented.orient(o.inverse()), g);
+    /// }
+    /// ```
+    pub fn orientations(&self) -> impl Iterator<Item = (Orientation, Self)> {
+        Orientation::iter().map(|o| (o, self.orient(o)))
+    }
+
+    fn swapped_size(&self) -> (usize, usize) {
+        (self.height(), self.width)
+    }
+
+    /// The size is passed in explicitly since it can't be derived from `cells` if it's empty, and
+    /// the `transform` functions can't produce empty rows from empty input.
+    fn from_transformed(cells: Vec<Vec<T>>, size: (usize, usize)) -> Self {
+        if size.0 == 0 || size.1 == 0 {
+            return Self {
+                cells: vec![Vec::new(); size.1],
+                width: size.0,
+            };
+        }
+        Self {
+            cells,
+            width: size.0,
+        }
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    /// Returns a new `Grid` surrounded by `padding` cells of `default` on every side. See
+    /// `pad_vec()`. Positions in the new `Grid` are offset by `padding` in both dimensions.
+    ///
+    /// ```
+    /// use aoclib_rs::grid::Grid;
+    /// let g = Grid::new(vec![vec![1, 2]]).unwrap().pad(1, 0);
+    /// assert_eq!(g.size(), (4, 3));
+    /// assert_eq!(g.rows(), [vec![0, 0, 0, 0], vec![0, 1, 2, 0], vec![0, 0, 0, 0]]);
+    /// ```
+    pub fn pad(&self, padding: usize, default: T) -> Self {
+        if self.cells.is_empty() {
+            return Self::filled(self.width + padding * 2, padding * 2, default);
+        }
+
+        Self {
+            cells: pad_vec(&self.cells, padding, default),
+            width: self.width + padding * 2,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        &self.cells[index.1][index.0]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        &mut self.cells[index.1][index.0]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for row in &self.cells {
+            for c in row {
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Positions of marker characters found by `Grid::parse_with_markers()` and
+/// `Grid::try_parse_with_markers()`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Markers(HashMap<char, Vec<(usize, usize)>>);
+
+impl Markers {
+    /// Returns the position of the first occurrence (left-to-right, top-to-bottom) of `marker`,
+    /// or `None` if it never occurred or wasn't requested.
+    pub fn get(&self, marker: char) -> Option<(usize, usize)> {
+        self.get_all(marker).first().copied()
+    }
+
+    /// Returns the positions of every occurrence (left-to-right, top-to-bottom) of `marker`.
+    pub fn get_all(&self, marker: char) -> &[(usize, usize)] {
+        self.0.get(&marker).map_or(&[], |v| v.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dir::Dir8;
+
+    #[test]
+    fn test_new_empty() {
+        let g: Grid<u8> = Grid::new(vec![]).unwrap();
+        assert_eq!(g.size(), (0, 0));
+        assert_eq!(g.positions().count(), 0);
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut g = Grid::filled(2, 2, 0);
+        *g.get_mut((1, 1)).unwrap() = 5;
+        g[(0, 1)] = 3;
+        assert_eq!(g.rows(), [vec![0, 0], vec![3, 5]]);
+        assert!(g.get_mut((2, 1)).is_none());
+    }
+
+    #[test]
+    fn test_iter_order() {
+        let g = Grid::new(vec![vec![1, 2], vec![3, 4]]).unwrap();
+        assert_eq!(
+            g.iter().collect::<Vec<_>>(),
+            [((0, 0), &1), ((1, 0), &2), ((0, 1), &3), ((1, 1), &4)]
+        );
+        assert_eq!(
+            g.positions().collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (0, 1), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn test_neighbours_dir8() {
+        let g = Grid::filled(3, 3, '.');
+        assert_eq!(g.neighbours::<Dir8>((1, 1)).count(), 8);
+        assert_eq!(g.neighbours::<Dir8>((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_pad_empty() {
+        let g: Grid<u8> = Grid::new(vec![]).unwrap().pad(1, 0);
+        assert_eq!(g.size(), (2, 2));
+        assert_eq!(Grid::from_fn(3, 0, |_| 0).pad(1, 0).size(), (5, 2));
+        assert_eq!(Grid::from_fn(0, 2, |_| 0).pad(1, 0).size(), (2, 4));
+    }
+
+    #[test]
+    fn test_parse_ragged_error() {
+        let err = Grid::parse(&["..", "...", ".."], |c| c).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ragged grid: line 2 has width 3 but line 1 has width 2"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_markers_multiple() {
+        let (g, markers) = Grid::parse_with_markers(&["a.a", ".a."], &['a', 'z'], |c| c).unwrap();
+        assert_eq!(g.size(), (3, 2));
+        assert_eq!(markers.get_all('a'), [(0, 0), (2, 0), (1, 1)]);
+        assert_eq!(markers.get('z'), None);
+        assert_eq!(markers.get('.'), None);
+    }
+
+    #[test]
+    fn test_transforms_keep_size() {
+        let g = Grid::filled(3, 2, 0);
+        assert_eq!(g.transpose().size(), (2, 3));
+        assert_eq!(g.rotate_right().size(), (2, 3));
+        assert_eq!(g.rotate_left().size(), (2, 3));
+        assert_eq!(g.rotate_180().size(), (3, 2));
+        assert_eq!(g.flip_horizontal().size(), (3, 2));
+        assert_eq!(g.flip_vertical().size(), (3, 2));
+        assert_eq!(g.orient(Orientation::new(1, true)).size(), (2, 3));
+
+        let g = Grid::from_fn(3, 0, |_| 0);
+        assert_eq!(g.transpose().size(), (0, 3));
+        assert_eq!(g.rotate_right().size(), (0, 3));
+        assert_eq!(g.rotate_left().transpose().size(), (3, 0));
+        assert_eq!(g.orient(Orientation::new(3, false)).size(), (0, 3));
+        assert_eq!(Grid::from_fn(0, 2, |_| 0).rotate_right().size(), (2, 0));
+    }
+
+    #[test]
+    fn test_display() {
+        let g = Grid::new(vec![vec!['#', '.'], vec!['.', '#']]).unwrap();
+        assert_eq!(g.to_string(), "#.\n.#\n");
+    }
+}
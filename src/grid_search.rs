@@ -0,0 +1,325 @@
+use std::ops::Add;
+
+use crate::{
+    dijkstra::search,
+    dir::{Dir4, Direction, Rotate90},
+    grid::Grid,
+};
+
+use num_traits::Zero;
+
+/// Rules for moving around a grid in `grid_search()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MovementRules<D> {
+    /// Extra cost of a step in a direction 90 degrees from the current facing. Reversing costs
+    /// twice this.
+    pub turn_cost: D,
+
+    /// Number of steps which must be taken in a straight line before turning or stopping at the
+    /// goal.
+    pub min_run: usize,
+
+    /// Maximum number of steps which can be taken in a straight line before having to turn.
+    pub max_run: usize,
+
+    /// Whether a step can be taken in the opposite direction to the current facing.
+    pub allow_reverse: bool,
+}
+
+impl<D: Zero> Default for MovementRules<D> {
+    /// Free turns, no limits on straight runs, and no reversing.
+    fn default() -> Self {
+        Self {
+            turn_cost: D::zero(),
+            min_run: 0,
+            max_run: usize::MAX,
+            allow_reverse: false,
+        }
+    }
+}
+
+/// A state in `grid_search()`: a position, the direction of the last step, and how many steps
+/// have been taken in a row in that direction.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GridState {
+    pos: (usize, usize),
+    facing: Option<Dir4>,
+    run: usize,
+}
+
+impl GridState {
+    pub fn pos(&self) -> (usize, usize) {
+        self.pos
+    }
+
+    /// `None` only for a starting state with no initial facing.
+    pub fn facing(&self) -> Option<Dir4> {
+        self.facing
+    }
+
+    /// Number of steps taken in a row in the direction of `facing()`, capped at the largest value
+    /// the `MovementRules` still distinguish (so that long straight runs don't multiply the number
+    /// of states). Zero for the starting state.
+    pub fn run(&self) -> usize {
+        self.run
+    }
+}
+
+/// Result of `grid_search()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridPath<D> {
+    cost: D,
+    states: Vec<GridState>,
+}
+
+impl<D: Copy> GridPath<D> {
+    pub fn cost(&self) -> D {
+        self.cost
+    }
+
+    /// Every state along the path, starting with the starting state and ending at the goal.
+    pub fn states(&self) -> &[GridState] {
+        &self.states
+    }
+
+    /// Every position along the path, starting with the start and ending at the goal.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.states.iter().map(|s| s.pos)
+    }
+}
+
+/// Finds the cheapest way to get from `start` to `goal` in `grid`, where each step moves to a
+/// neighbouring cell in one of the directions of `Dir4`, subject to `rules`. Entering a cell costs
+/// whatever `cost` returns for it, or is impossible if `cost` returns `None`. Steps which change
+/// direction also cost `rules.turn_cost` (twice that for reversing). The first step is free of
+/// turning costs if `start_facing` is `None`. Returns `None` if the goal can't be reached.
+///
+/// ```
+/// use aoclib_rs::{
+///     dir::Dir4,
+///     grid::Grid,
+///     grid_search::{MovementRules, grid_search},
+/// };
+///
+/// // A crucible which must turn after at most 3 blocks.
+/// let g = Grid::parse(&["11199", "99119", "99911"], |c| c.to_digit(10).unwrap()).unwrap();
+/// let rules = MovementRules {
+///     max_run: 3,
+///     ..MovementRules::default()
+/// };
+/// let p = grid_search(&g, (0, 0), None, (4, 2), rules, |&c| Some(c)).unwrap();
+/// assert_eq!(p.cost(), 6);
+/// assert_eq!(p.states().last().unwrap().facing(), Some(Dir4::Right));
+/// ```
+pub fn grid_search<T, D, C>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    start_facing: Option<Dir4>,
+    goal: (usize, usize),
+    rules: MovementRules<D>,
+    mut cost: C,
+) -> Option<GridPath<D>>
+where
+    D: Copy + Add<Output = D> + Ord + Zero,
+    C: FnMut(&T) -> Option<D>,
+{
+    // Runs longer than this all behave the same under `rules`.
+    let max_tracked_run = if rules.max_run == usize::MAX {
+        rules.min_run.max(1)
+    } else {
+        rules.max_run
+    };
+    let start = GridState {
+        pos: start,
+        facing: start_facing,
+        run: 0,
+    };
+    let r = search(
+        start,
+        |s: GridState| {
+            let mut v = Vec::new();
+            for d in Dir4::iter() {
+                let Some(turn) = turn_cost(s, d, &rules) else {
+                    continue;
+                };
+                let Some(pos) = d.ray(s.pos, grid.size()).next() else {
+                    continue;
+                };
+                let Some(c) = cost(&grid[pos]) else {
+                    continue;
+                };
+
+                let run = if s.facing == Some(d) { s.run + 1 } else { 1 };
+                let run = run.min(max_tracked_run);
+                v.push((
+                    GridState {
+                        pos,
+                        facing: Some(d),
+                        run,
+                    },
+                    turn + c,
+                ));
+            }
+            v
+        },
+        |s| s.pos == goal && (s.run == 0 || s.run >= rules.min_run),
+    );
+
+    let (_, cost) = r.goal()?;
+    Some(GridPath {
+        cost,
+        states: r.goal_path()?,
+    })
+}
+
+/// Returns the turning cost of stepping in direction `d` from state `s`, or `None` if `rules`
+/// forbid it.
+fn turn_cost<D>(s: GridState, d: Dir4, rules: &MovementRules<D>) -> Option<D>
+where
+    D: Copy + Add<Output = D> + Zero,
+{
+    let Some(facing) = s.facing else {
+        return Some(D::zero());
+    };
+
+    if d == facing {
+        return if s.run < rules.max_run {
+            Some(D::zero())
+        } else {
+            None
+        };
+    }
+
+    // Starting states can turn straight away.
+    if s.run != 0 && s.run < rules.min_run {
+        return None;
+    }
+
+    if d == facing.rotate_right_90() || d == facing.rotate_left_90() {
+        Some(rules.turn_cost)
+    } else if rules.allow_reverse {
+        Some(rules.turn_cost + rules.turn_cost)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crucible_grid(lines: &[&str]) -> Grid<u32> {
+        Grid::parse(lines, |c| c.to_digit(10).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_crucibles() {
+        // Examples from Advent of Code 2023 day 17.
+        let g = crucible_grid(&[
+            "2413432311323",
+            "3215453535623",
+            "3255245654254",
+            "3446585845452",
+            "4546657867536",
+            "1438598798454",
+            "4457876987766",
+            "3637877979653",
+            "4654967986887",
+            "4564679986453",
+            "1224686865563",
+            "2546548887735",
+            "4322674655533",
+        ]);
+        let goal = (g.width() - 1, g.height() - 1);
+
+        let rules = MovementRules {
+            max_run: 3,
+            ..MovementRules::default()
+        };
+        let p = grid_search(&g, (0, 0), None, goal, rules, |&c| Some(c)).unwrap();
+        assert_eq!(p.cost(), 102);
+        assert_eq!(p.positions().next(), Some((0, 0)));
+        assert_eq!(p.positions().last(), Some(goal));
+        assert!(p.states().iter().all(|s| s.run() <= 3));
+
+        let ultra = MovementRules {
+            min_run: 4,
+            max_run: 10,
+            ..MovementRules::default()
+        };
+        let p = grid_search(&g, (0, 0), None, goal, ultra, |&c| Some(c)).unwrap();
+        assert_eq!(p.cost(), 94);
+
+        let g = crucible_grid(&[
+            "111111111111",
+            "999999999991",
+            "999999999991",
+            "999999999991",
+            "999999999991",
+        ]);
+        let goal = (g.width() - 1, g.height() - 1);
+        let p = grid_search(&g, (0, 0), None, goal, ultra, |&c| Some(c)).unwrap();
+        assert_eq!(p.cost(), 71);
+    }
+
+    #[test]
+    fn test_reindeer_maze() {
+        // Example from Advent of Code 2024 day 16.
+        let lines = [
+            "###############",
+            "#.......#....E#",
+            "#.#.###.#.###.#",
+            "#.....#.#...#.#",
+            "#.###.#####.#.#",
+            "#.#.#.......#.#",
+            "#.#.#####.###.#",
+            "#...........#.#",
+            "###.#.#####.#.#",
+            "#...#.....#.#.#",
+            "#.#.#.###.#.#.#",
+            "#.....#...#.#.#",
+            "#.###.#.#.#.#.#",
+            "#S..#.....#...#",
+            "###############",
+        ];
+        let (g, markers) = Grid::parse_with_markers(&lines, &['S', 'E'], |c| c).unwrap();
+        let (start, end) = (markers.get('S').unwrap(), markers.get('E').unwrap());
+        let rules = MovementRules {
+            turn_cost: 1000,
+            allow_reverse: true,
+            ..MovementRules::default()
+        };
+        let p = grid_search(&g, start, Some(Dir4::Right), end, rules, |&c| {
+            (c != '#').then_some(1)
+        })
+        .unwrap();
+        assert_eq!(p.cost(), 7036);
+    }
+
+    #[test]
+    fn test_large_open_grid() {
+        let lines = vec![".".repeat(141); 141];
+        let lines: Vec<_> = lines.iter().map(|l| l.as_str()).collect();
+        let g = Grid::parse(&lines, |c| c).unwrap();
+        let p = grid_search(
+            &g,
+            (0, 0),
+            None,
+            (140, 140),
+            MovementRules::default(),
+            |_| Some(1),
+        )
+        .unwrap();
+        assert_eq!(p.cost(), 280);
+        assert_eq!(p.positions().count(), 281);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let g = crucible_grid(&["10", "01"]);
+        let p = grid_search(&g, (0, 0), None, (1, 1), MovementRules::default(), |&c| {
+            (c != 0).then_some(c)
+        });
+        assert_eq!(p, None);
+    }
+}
@@ -0,0 +1,295 @@
+use std::str::FromStr;
+
+use crate::{abs, dir::Direction};
+
+/// A `Direction` whose `delta()` is a `(q, r)` offset between neighbouring hexagons, suitable for
+/// moving a `HexPoint`.
+pub trait HexDirection: Direction {}
+
+/// The six directions of a hexagonal grid whose hexagons have a flat top and bottom (so the
+/// neighbours are directly above and below, but not directly left and right). `delta()` returns
+/// `(q, r)` offsets for use with `HexPoint`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FlatHexDir {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl Direction for FlatHexDir {
+    fn delta(self) -> (i8, i8) {
+        match self {
+            FlatHexDir::N => (0, -1),
+            FlatHexDir::NE => (1, -1),
+            FlatHexDir::SE => (1, 0),
+            FlatHexDir::S => (0, 1),
+            FlatHexDir::SW => (-1, 1),
+            FlatHexDir::NW => (-1, 0),
+        }
+    }
+
+    fn rotate_right(self) -> FlatHexDir {
+        match self {
+            FlatHexDir::N => FlatHexDir::NE,
+            FlatHexDir::NE => FlatHexDir::SE,
+            FlatHexDir::SE => FlatHexDir::S,
+            FlatHexDir::S => FlatHexDir::SW,
+            FlatHexDir::SW => FlatHexDir::NW,
+            FlatHexDir::NW => FlatHexDir::N,
+        }
+    }
+
+    fn rotate_left(self) -> FlatHexDir {
+        self.opposite().rotate_right().rotate_right()
+    }
+
+    fn opposite(self) -> FlatHexDir {
+        self.rotate_right().rotate_right().rotate_right()
+    }
+
+    fn iter() -> impl Iterator<Item = FlatHexDir> {
+        Self::iter_from(FlatHexDir::N)
+    }
+}
+
+impl HexDirection for FlatHexDir {}
+
+impl FromStr for FlatHexDir {
+    type Err = anyhow::Error;
+
+    /// Parses lowercase or uppercase compass notation (`"n"`, `"ne"`, `"se"`, `"s"`, `"sw"`,
+    /// `"nw"`).
+    ///
+    /// ```
+    /// use aoclib_rs::hex::FlatHexDir;
+    /// assert_eq!("ne".parse::<FlatHexDir>().unwrap(), FlatHexDir::NE);
+    /// assert!("e".parse::<FlatHexDir>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "n" => FlatHexDir::N,
+            "ne" => FlatHexDir::NE,
+            "se" => FlatHexDir::SE,
+            "s" => FlatHexDir::S,
+            "sw" => FlatHexDir::SW,
+            "nw" => FlatHexDir::NW,
+            _ => anyhow::bail!("invalid flat hex direction: {}", s),
+        })
+    }
+}
+
+/// The six directions of a hexagonal grid whose hexagons have a pointy top and bottom (so the
+/// neighbours are directly left and right, but not directly above and below). `delta()` returns
+/// `(q, r)` offsets for use with `HexPoint`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PointyHexDir {
+    NE,
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction for PointyHexDir {
+    fn delta(self) -> (i8, i8) {
+        match self {
+            PointyHexDir::NE => (1, -1),
+            PointyHexDir::E => (1, 0),
+            PointyHexDir::SE => (0, 1),
+            PointyHexDir::SW => (-1, 1),
+            PointyHexDir::W => (-1, 0),
+            PointyHexDir::NW => (0, -1),
+        }
+    }
+
+    fn rotate_right(self) -> PointyHexDir {
+        match self {
+            PointyHexDir::NE => PointyHexDir::E,
+            PointyHexDir::E => PointyHexDir::SE,
+            PointyHexDir::SE => PointyHexDir::SW,
+            PointyHexDir::SW => PointyHexDir::W,
+            PointyHexDir::W => PointyHexDir::NW,
+            PointyHexDir::NW => PointyHexDir::NE,
+        }
+    }
+
+    fn rotate_left(self) -> PointyHexDir {
+        self.opposite().rotate_right().rotate_right()
+    }
+
+    fn opposite(self) -> PointyHexDir {
+        self.rotate_right().rotate_right().rotate_right()
+    }
+
+    fn iter() -> impl Iterator<Item = PointyHexDir> {
+        Self::iter_from(PointyHexDir::NE)
+    }
+}
+
+impl HexDirection for PointyHexDir {}
+
+impl FromStr for PointyHexDir {
+    type Err = anyhow::Error;
+
+    /// Parses lowercase or uppercase compass notation (`"ne"`, `"e"`, `"se"`, `"sw"`, `"w"`,
+    /// `"nw"`).
+    ///
+    /// ```
+    /// use aoclib_rs::hex::PointyHexDir;
+    /// assert_eq!("SW".parse::<PointyHexDir>().unwrap(), PointyHexDir::SW);
+    /// assert!("n".parse::<PointyHexDir>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "ne" => PointyHexDir::NE,
+            "e" => PointyHexDir::E,
+            "se" => PointyHexDir::SE,
+            "sw" => PointyHexDir::SW,
+            "w" => PointyHexDir::W,
+            "nw" => PointyHexDir::NW,
+            _ => anyhow::bail!("invalid pointy hex direction: {}", s),
+        })
+    }
+}
+
+/// A hexagon in a hexagonal grid, in axial coordinates (see
+/// <https://www.redblobgames.com/grids/hexagons/#coordinates-axial>). The third cube coordinate is
+/// available as `s()`. Works with both `FlatHexDir` and `PointyHexDir`, though a single grid
+/// should only ever use one of them.
+///
+/// ```
+/// use aoclib_rs::hex::{HexPoint, PointyHexDir};
+/// let mut p = HexPoint::new(0, 0);
+/// for d in "e,se,w,nw,ne,ne,ne".split(',') {
+///     p = p.neighbour(d.parse::<PointyHexDir>().unwrap());
+/// }
+/// assert_eq!(p, HexPoint::new(3, -3));
+/// assert_eq!(p.distance(&HexPoint::new(0, 0)), 3);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct HexPoint {
+    q: i64,
+    r: i64,
+}
+
+impl HexPoint {
+    pub fn new(q: i64, r: i64) -> Self {
+        Self { q, r }
+    }
+
+    /// Creates a `HexPoint` from cube coordinates. Returns an error if `q + r + s != 0`.
+    pub fn from_cube(q: i64, r: i64, s: i64) -> anyhow::Result<Self> {
+        if q + r + s != 0 {
+            anyhow::bail!("invalid cube coordinates: {} + {} + {} != 0", q, r, s);
+        }
+        Ok(Self::new(q, r))
+    }
+
+    pub fn q(&self) -> i64 {
+        self.q
+    }
+
+    pub fn r(&self) -> i64 {
+        self.r
+    }
+
+    /// The third cube coordinate, derived from the other two.
+    pub fn s(&self) -> i64 {
+        -self.q - self.r
+    }
+
+    /// Number of steps between `self` and `other`.
+    ///
+    /// ```
+    /// use aoclib_rs::hex::HexPoint;
+    /// assert_eq!(HexPoint::new(1, -3).distance(&HexPoint::new(-2, 1)), 4);
+    /// ```
+    pub fn distance(&self, other: &Self) -> i64 {
+        (abs(self.q - other.q) + abs(self.r - other.r) + abs(self.s() - other.s())) / 2
+    }
+
+    /// Returns the adjacent `HexPoint` in direction `d`.
+    pub fn neighbour<D: HexDirection>(&self, d: D) -> Self {
+        self.neighbour_n(d, 1)
+    }
+
+    /// Returns the `HexPoint` `n` steps away in direction `d`.
+    pub fn neighbour_n<D: HexDirection>(&self, d: D, n: i64) -> Self {
+        let (dq, dr) = d.delta();
+        Self::new(self.q + i64::from(dq) * n, self.r + i64::from(dr) * n)
+    }
+
+    /// Iterates through all 6 adjacent `HexPoint`s, in the order of `D::iter()`.
+    ///
+    /// ```
+    /// use aoclib_rs::hex::{FlatHexDir, HexPoint};
+    /// let p = HexPoint::new(2, 5);
+    /// assert!(p.neighbours::<FlatHexDir>().all(|n| n.distance(&p) == 1));
+    /// ```
+    pub fn neighbours<D: HexDirection>(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        D::iter().map(move |d| p.neighbour(d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_dirs<D: HexDirection + std::fmt::Debug>() {
+        let all: Vec<D> = D::iter().collect();
+        assert_eq!(all.len(), 6);
+        for d in all {
+            assert_eq!(d.opposite().opposite(), d);
+            assert_eq!(d.rotate_right().rotate_left(), d);
+            let (dq, dr) = d.delta();
+            let (oq, or) = d.opposite().delta();
+            assert_eq!((dq + oq, dr + or), (0, 0));
+            assert_eq!(
+                HexPoint::new(0, 0)
+                    .neighbour(d)
+                    .distance(&HexPoint::new(0, 0)),
+                1
+            );
+        }
+    }
+
+    #[test]
+    fn test_flat_dirs() {
+        check_dirs::<FlatHexDir>();
+        assert_eq!(FlatHexDir::N.rotate_left(), FlatHexDir::NW);
+        assert_eq!(FlatHexDir::SE.opposite(), FlatHexDir::NW);
+    }
+
+    #[test]
+    fn test_pointy_dirs() {
+        check_dirs::<PointyHexDir>();
+        assert_eq!(PointyHexDir::NE.rotate_left(), PointyHexDir::NW);
+        assert_eq!(PointyHexDir::E.opposite(), PointyHexDir::W);
+    }
+
+    #[test]
+    fn test_flat_path_distance() {
+        // Examples from Advent of Code 2017 day 11.
+        let walk = |s: &str| {
+            s.split(',')
+                .map(|d| d.parse::<FlatHexDir>().unwrap())
+                .fold(HexPoint::new(0, 0), |p, d| p.neighbour(d))
+                .distance(&HexPoint::new(0, 0))
+        };
+        assert_eq!(walk("ne,ne,ne"), 3);
+        assert_eq!(walk("ne,ne,sw,sw"), 0);
+        assert_eq!(walk("ne,ne,s,s"), 2);
+        assert_eq!(walk("se,sw,se,sw,sw"), 3);
+    }
+
+    #[test]
+    fn test_from_cube() {
+        assert_eq!(HexPoint::from_cube(1, 2, -3).unwrap(), HexPoint::new(1, 2));
+        assert!(HexPoint::from_cube(1, 2, 3).is_err());
+    }
+}
@@ -1,11 +1,23 @@
 pub mod binary_search;
 pub mod dijkstra;
 pub mod dir;
+pub mod dir_set;
+pub mod dist_store;
+pub mod graph;
+pub mod grid;
+pub mod grid_search;
+pub mod hex;
 pub mod iter;
 pub mod matrix;
 pub mod option_min_max;
+pub mod pattern;
 pub mod point;
+pub mod region;
+pub mod sparse_grid;
+pub mod stencil;
+pub mod transform;
 pub mod trie;
+pub mod walker;
 
 use std::{
     fmt,
@@ -0,0 +1,216 @@
+use crate::{grid::Grid, transform::Orientation};
+
+/// A small rectangular 2-dimensional pattern (interpreted as in `position_2d()`) to search for
+/// inside larger 2-dimensional data. `None` cells are wildcards, matching anything.
+///
+/// Note that orientations are only rotations by multiples of 90 degrees and reflections, so to
+/// find (for example) a word written diagonally, the `Pattern` itself needs to be diagonal, with
+/// wildcards filling in the rest.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern<T> {
+    cells: Vec<Vec<Option<T>>>,
+}
+
+/// A location where a `Pattern` was found by `Pattern::find_in()` or
+/// `Pattern::find_in_orientations()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    pos: (usize, usize),
+    orientation: Orientation,
+}
+
+impl PatternMatch {
+    /// Position of the top-left corner of the (oriented) `Pattern` within the searched data.
+    pub fn pos(&self) -> (usize, usize) {
+        self.pos
+    }
+
+    /// The `Orientation` which was applied to the `Pattern` to produce this match.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+}
+
+impl<T> Pattern<T> {
+    /// Returns an error if `cells` is empty or the rows are not all the same length.
+    pub fn new(cells: Vec<Vec<Option<T>>>) -> anyhow::Result<Self> {
+        let g = Grid::new(cells)?;
+        if g.width() == 0 {
+            anyhow::bail!("empty pattern");
+        }
+        Ok(Self {
+            cells: g.into_rows(),
+        })
+    }
+
+    /// Parses a `Pattern` from lines of characters, treating `wildcard` as a wildcard and
+    /// converting every other character with `f`.
+    ///
+    /// ```
+    /// use aoclib_rs::pattern::Pattern;
+    /// let p = Pattern::parse(&["#?", "?#"], '?', |c| c).unwrap();
+    /// assert_eq!(p.size(), (2, 2));
+    /// ```
+    pub fn parse<F>(lines: &[&str], wildcard: char, mut f: F) -> anyhow::Result<Self>
+    where
+        F: FnMut(char) -> T,
+    {
+        Self::new(
+            Grid::parse(lines, |c| if c == wildcard { None } else { Some(f(c)) })?.into_rows(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `(width, height)`.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width(), self.height())
+    }
+}
+
+impl<T: Clone + PartialEq> Pattern<T> {
+    /// Returns every (possibly overlapping) match of the `Pattern` in `haystack`, without
+    /// rotating or reflecting it. Matches are in left-to-right, top-to-bottom order. The caller is
+    /// responsible for making sure each row of `haystack` is the same size.
+    ///
+    /// ```
+    /// use aoclib_rs::pattern::Pattern;
+    /// let p = Pattern::parse(&["ab"], '?', |c| c).unwrap();
+    /// let haystack = vec!["abab".chars().collect(), "baba".chars().collect()];
+    /// let matches: Vec<_> = p.find_in(&haystack).iter().map(|m| m.pos()).collect();
+    /// assert_eq!(matches, [(0, 0), (2, 0), (1, 1)]);
+    /// ```
+    pub fn find_in(&self, haystack: &[Vec<T>]) -> Vec<PatternMatch> {
+        self.find_in_orientations(haystack, [Orientation::IDENTITY])
+    }
+
+    /// Returns every (possibly overlapping) match of the `Pattern` in `haystack`, trying each of
+    /// the given `orientations` in turn (use `Orientation::iter()` for all 8). Orientations which
+    /// produce the same shape as an earlier one (because the `Pattern` is symmetric) are skipped,
+    /// so each match is only reported once. The caller is responsible for making sure each row of
+    /// `haystack` is the same size.
+    ///
+    /// ```
+    /// use aoclib_rs::{pattern::Pattern, transform::Orientation};
+    /// let p = Pattern::parse(&["#.", "##"], '.', |c| c).unwrap();
+    /// let haystack = vec!["##.".chars().collect(), "#..".chars().collect()];
+    /// let matches = p.find_in_orientations(&haystack, Orientation::iter());
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].pos(), (0, 0));
+    /// assert_eq!(matches[0].orientation(), Orientation::new(1, false));
+    /// ```
+    pub fn find_in_orientations<I>(&self, haystack: &[Vec<T>], orientations: I) -> Vec<PatternMatch>
+    where
+        I: IntoIterator<Item = Orientation>,
+    {
+        let mut seen: Vec<Vec<Vec<Option<T>>>> = Vec::new();
+        let mut matches = Vec::new();
+        for orientation in orientations {
+            let oriented = orientation.apply(&self.cells);
+            if seen.contains(&oriented) {
+                continue;
+            }
+
+            matches.extend(
+                find_oriented(&oriented, haystack)
+                    .into_iter()
+                    .map(|pos| PatternMatch { pos, orientation }),
+            );
+            seen.push(oriented);
+        }
+        matches
+    }
+
+    /// Returns the positions within the searched data of every non-wildcard cell of `m`.
+    ///
+    /// ```
+    /// use aoclib_rs::pattern::Pattern;
+    /// let p = Pattern::parse(&["#.", "##"], '.', |c| c).unwrap();
+    /// let haystack = vec!["###".chars().collect(), ".##".chars().collect()];
+    /// let m = p.find_in(&haystack)[0];
+    /// assert_eq!(p.matched_cells(&m), [(1, 0), (1, 1), (2, 1)]);
+    /// ```
+    pub fn matched_cells(&self, m: &PatternMatch) -> Vec<(usize, usize)> {
+        m.orientation
+            .apply(&self.cells)
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, c)| c.is_some())
+                    .map(move |(x, _)| (m.pos.0 + x, m.pos.1 + y))
+            })
+            .collect()
+    }
+}
+
+fn find_oriented<T: PartialEq>(
+    pattern: &[Vec<Option<T>>],
+    haystack: &[Vec<T>],
+) -> Vec<(usize, usize)> {
+    let (p_width, p_height) = (pattern[0].len(), pattern.len());
+    let (h_width, h_height) = (haystack.first().map_or(0, |row| row.len()), haystack.len());
+    if p_width > h_width || p_height > h_height {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    for y in 0..=(h_height - p_height) {
+        for x in 0..=(h_width - p_width) {
+            let is_match = pattern.iter().enumerate().all(|(py, row)| {
+                row.iter().enumerate().all(|(px, c)| match c {
+                    None => true,
+                    Some(c) => *c == haystack[y + py][x + px],
+                })
+            });
+            if is_match {
+                matches.push((x, y));
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_empty() {
+        assert!(Pattern::<char>::new(vec![]).is_err());
+        assert!(Pattern::<char>::new(vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_pattern_larger_than_haystack() {
+        let p = Pattern::parse(&["abc"], '?', |c| c).unwrap();
+        assert!(p.find_in(&[vec!['a', 'b']]).is_empty());
+        assert!(p.find_in(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_symmetric_pattern_deduped() {
+        let p = Pattern::parse(&["XMAS"], '?', |c| c).unwrap();
+        let haystack: Vec<Vec<char>> = ["XMAS", "M..A", "A..M", "SAMX"]
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect();
+        let matches = p.find_in_orientations(&haystack, Orientation::iter());
+        assert_eq!(matches.len(), 4);
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let p = Pattern::parse(&["a?a"], '?', |c| c).unwrap();
+        let haystack = vec!["abaxa".chars().collect::<Vec<_>>()];
+        let matches: Vec<_> = p.find_in(&haystack).iter().map(|m| m.pos()).collect();
+        assert_eq!(matches, [(0, 0), (2, 0)]);
+    }
+}
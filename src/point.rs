@@ -3,12 +3,12 @@ use std::{
     fmt::Debug,
     hash::Hash,
     iter::zip,
-    ops::{DivAssign, Rem, Sub},
+    ops::{Add, DivAssign, Rem, Sub},
 };
 
 use crate::abs;
 
-use num_traits::{NumCast, Zero};
+use num_traits::{NumCast, One, Zero};
 
 /// A point that can be initialized with an array of the appropriate size.
 pub trait PointFromArray<T, const N: usize> {
@@ -32,7 +32,7 @@ pub trait PointManhattan<T> {
 
 /// 2-dimensional point.
 /// For full functionality for floats, try the `ordered_float` crate
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Point2d<T: Copy>(PointNd<T, 2>);
 
 impl<T: Copy> Point2d<T> {
@@ -117,7 +117,7 @@ where
 
 /// 3-dimensional point.
 /// For full functionality for floats, try the `ordered_float` crate
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Point3d<T: Copy>(PointNd<T, 3>);
 
 impl<T: Copy> Point3d<T> {
@@ -176,7 +176,7 @@ where
 
 /// N-dimensional point.
 /// For full functionality for floats, try the `ordered_float` crate
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct PointNd<T: Copy, const N: usize> {
     vals: [T; N],
 }
@@ -191,6 +191,43 @@ impl<T: Copy, const N: usize> PointNd<T, N> {
     }
 }
 
+impl<T, const N: usize> PointNd<T, N>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + One,
+{
+    /// Iterates through all `3^N - 1` neighbours of `self`, ie, every point whose coordinates each
+    /// differ by at most one.
+    ///
+    /// ```
+    /// use aoclib_rs::point::PointNd;
+    /// assert_eq!(PointNd::new([0, 0, 0, 0]).neighbours().count(), 80);
+    /// assert_eq!(
+    ///     PointNd::new([5]).neighbours().collect::<Vec<_>>(),
+    ///     [PointNd::new([4]), PointNd::new([6])]
+    /// );
+    /// ```
+    pub fn neighbours(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        (0..3usize.pow(u32::try_from(N).unwrap())).filter_map(move |mut i| {
+            if i == (3usize.pow(u32::try_from(N).unwrap()) - 1) / 2 {
+                // All coordinates unchanged.
+                return None;
+            }
+
+            let mut vals = p.vals;
+            for v in vals.iter_mut() {
+                match i % 3 {
+                    0 => *v = *v - T::one(),
+                    1 => {}
+                    _ => *v = *v + T::one(),
+                }
+                i /= 3;
+            }
+            Some(Self::new(vals))
+        })
+    }
+}
+
 impl<T: Copy, const N: usize> PointFromArray<T, N> for PointNd<T, N> {
     fn from_array(vals: [T; N]) -> Self {
         Self::new(vals)
@@ -444,6 +481,21 @@ mod tests {
         get_angle_good_helper(|x: f64, y: f64| get_angle(x, y).unwrap());
     }
 
+    #[test]
+    fn test_point_nd_neighbours_distinct() {
+        let p = PointNd::new([1, -4, 7]);
+        let mut neighbours: Vec<_> = p.neighbours().map(|n| n.vals).collect();
+        assert_eq!(neighbours.len(), 26);
+        assert!(
+            neighbours
+                .iter()
+                .all(|n| n != &p.vals && p.manhattan(&PointNd::new(*n)) <= 3)
+        );
+        neighbours.sort();
+        neighbours.dedup();
+        assert_eq!(neighbours.len(), 26);
+    }
+
     #[test]
     fn test_get_angle_zero() {
         assert!(get_angle(0, 0).is_err());
@@ -0,0 +1,337 @@
+use std::collections::{HashSet, VecDeque, hash_set};
+
+use crate::dir::{Dir4, Dir8, Direction, Rotate90};
+
+/// A set of cells in 2-dimensional data (interpreted as in `position_2d()`), typically one
+/// connected area found by `flood_fill()` or `label_components()`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Region {
+    cells: HashSet<(usize, usize)>,
+}
+
+impl Region {
+    pub fn new() -> Self {
+        Self {
+            cells: HashSet::new(),
+        }
+    }
+
+    pub fn cells(&self) -> &HashSet<(usize, usize)> {
+        &self.cells
+    }
+
+    /// Number of cells in the `Region`.
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, pos: (usize, usize)) -> bool {
+        self.cells.contains(&pos)
+    }
+
+    pub fn insert(&mut self, pos: (usize, usize)) -> bool {
+        self.cells.insert(pos)
+    }
+
+    /// Iterates through the cells of the `Region`, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> {
+        self.cells.iter().copied()
+    }
+
+    /// Same as `size()`.
+    pub fn area(&self) -> usize {
+        self.size()
+    }
+
+    /// Number of cell edges separating the `Region` from the outside (including any holes), ie,
+    /// the length of fence needed to surround it.
+    ///
+    /// ```
+    /// use aoclib_rs::region::Region;
+    /// let r: Region = [(0, 0), (1, 0), (1, 1)].into_iter().collect();
+    /// assert_eq!(r.perimeter(), 8);
+    /// ```
+    pub fn perimeter(&self) -> usize {
+        self.iter()
+            .map(|pos| {
+                Dir4::iter()
+                    .filter(|&d| !self.contains_delta(pos, d.delta()))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight sides (maximal runs of fence) of the `Region`, including the sides of
+    /// any holes. Computed by counting corners, which is always equal to the number of sides.
+    ///
+    /// ```
+    /// use aoclib_rs::region::Region;
+    /// let r: Region = [(0, 0), (1, 0), (1, 1)].into_iter().collect();
+    /// assert_eq!(r.sides(), 6);
+    ///
+    /// // 3x3 square with a hole in the middle.
+    /// let r: Region = (0..3)
+    ///     .flat_map(|y| (0..3).map(move |x| (x, y)))
+    ///     .filter(|&pos| pos != (1, 1))
+    ///     .collect();
+    /// assert_eq!(r.sides(), 8);
+    /// ```
+    pub fn sides(&self) -> usize {
+        self.iter()
+            .map(|pos| {
+                Dir4::iter()
+                    .filter(|&d| {
+                        let a = self.contains_delta(pos, d.delta());
+                        let b = self.contains_delta(pos, d.rotate_right_90().delta());
+                        let diag = self.contains_delta(pos, Dir8::Dir4(d).rotate_right().delta());
+
+                        // Outer corner, or inner corner.
+                        (!a && !b) || (a && b && !diag)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Whether the cell at `pos` offset by `delta` is in the `Region`.
+    fn contains_delta(&self, pos: (usize, usize), delta: (i8, i8)) -> bool {
+        let (Some(x), Some(y)) = (
+            pos.0.checked_add_signed(isize::from(delta.0)),
+            pos.1.checked_add_signed(isize::from(delta.1)),
+        ) else {
+            return false;
+        };
+        self.contains((x, y))
+    }
+}
+
+impl FromIterator<(usize, usize)> for Region {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for Region {
+    type Item = (usize, usize);
+    type IntoIter = hash_set::IntoIter<(usize, usize)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+/// Returns the `Region` of cells reachable from `start` by moving in the directions of `D`, only
+/// ever passing through cells for which `predicate` returns `true`. If `predicate` returns `false`
+/// for `start`, the `Region` is empty. The caller is responsible for making sure each row is the
+/// same size.
+///
+/// ```
+/// use aoclib_rs::{dir::{Dir4, Dir8}, region::flood_fill};
+/// let v = vec![
+///     vec!['.', '#', '.'],
+///     vec!['#', '.', '.'],
+///     vec!['.', '.', '#'],
+/// ];
+/// assert_eq!(flood_fill::<Dir4, _, _>(&v, (0, 0), |&c| c == '.').size(), 1);
+/// assert_eq!(flood_fill::<Dir8, _, _>(&v, (0, 0), |&c| c == '.').size(), 6);
+/// ```
+pub fn flood_fill<D, T, P>(v: &[Vec<T>], start: (usize, usize), mut predicate: P) -> Region
+where
+    D: Direction,
+    P: FnMut(&T) -> bool,
+{
+    let mut region = Region::new();
+    if !predicate(&v[start.1][start.0]) {
+        return region;
+    }
+
+    let size = (v.first().map_or(0, |row| row.len()), v.len());
+    let mut q = VecDeque::from([start]);
+    region.insert(start);
+    while let Some(curr) = q.pop_front() {
+        for n in D::iter_valid_usizes_deltas(curr, size) {
+            if !region.contains(n) && predicate(&v[n.1][n.0]) {
+                region.insert(n);
+                q.push_back(n);
+            }
+        }
+    }
+
+    region
+}
+
+/// Result of `label_components()`: every connected `Region`, along with a map from each cell to
+/// the label (index) of the `Region` containing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Labelling {
+    labels: Vec<Vec<Option<usize>>>,
+    regions: Vec<Region>,
+}
+
+impl Labelling {
+    /// Returns the label of the `Region` containing `pos`, or `None` if `pos` was excluded (or is
+    /// out of bounds).
+    pub fn label(&self, pos: (usize, usize)) -> Option<usize> {
+        *self.labels.get(pos.1)?.get(pos.0)?
+    }
+
+    /// Map from each cell to the label of the `Region` containing it, shaped like the input.
+    pub fn label_map(&self) -> &[Vec<Option<usize>>] {
+        &self.labels
+    }
+
+    /// All `Region`s, indexed by label. Labels are assigned in the order in which each `Region`'s
+    /// first cell appears (left-to-right, top-to-bottom).
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Can panic if `label` is out of range.
+    pub fn region(&self, label: usize) -> &Region {
+        &self.regions[label]
+    }
+
+    /// Number of `Region`s.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+/// Splits 2-dimensional data into connected `Region`s. Only cells for which `include` returns
+/// `true` are labelled. Two included cells which are adjacent in one of the directions of `D` are
+/// in the same `Region` if `connected` returns `true` for them. The caller is responsible for
+/// making sure each row is the same size.
+///
+/// ```
+/// use aoclib_rs::{dir::Dir4, region::label_components};
+/// let v = vec![
+///     vec!['A', 'A', 'B'],
+///     vec!['C', 'A', 'B'],
+///     vec!['A', 'C', 'C'],
+/// ];
+///
+/// // Garden plots: every cell is included, and cells of the same type are connected.
+/// let plots = label_components::<Dir4, _, _, _>(&v, |_| true, |a, b| a == b);
+/// assert_eq!(plots.len(), 5);
+/// assert_eq!(plots.label((1, 1)), Some(0));
+/// assert_eq!(plots.region(0).size(), 3);
+///
+/// // Basins: `'C'` cells are walls, everything else is connected.
+/// let basins = label_components::<Dir4, _, _, _>(&v, |&c| c != 'C', |_, _| true);
+/// assert_eq!(basins.len(), 2);
+/// assert_eq!(basins.label((0, 1)), None);
+/// ```
+pub fn label_components<D, T, I, C>(v: &[Vec<T>], mut include: I, mut connected: C) -> Labelling
+where
+    D: Direction,
+    I: FnMut(&T) -> bool,
+    C: FnMut(&T, &T) -> bool,
+{
+    let size = (v.first().map_or(0, |row| row.len()), v.len());
+    let mut labels = vec![vec![None; size.0]; size.1];
+    let mut regions = Vec::new();
+
+    for y in 0..size.1 {
+        for x in 0..size.0 {
+            if labels[y][x].is_some() || !include(&v[y][x]) {
+                continue;
+            }
+
+            let label = regions.len();
+            let mut region = Region::new();
+            let mut q = VecDeque::from([(x, y)]);
+            labels[y][x] = Some(label);
+            region.insert((x, y));
+            while let Some(curr) = q.pop_front() {
+                for n in D::iter_valid_usizes_deltas(curr, size) {
+                    if labels[n.1][n.0].is_none()
+                        && include(&v[n.1][n.0])
+                        && connected(&v[curr.1][curr.0], &v[n.1][n.0])
+                    {
+                        labels[n.1][n.0] = Some(label);
+                        region.insert(n);
+                        q.push_back(n);
+                    }
+                }
+            }
+            regions.push(region);
+        }
+    }
+
+    Labelling { labels, regions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dir::Dir4;
+
+    #[test]
+    fn test_flood_fill_start_excluded() {
+        let v = vec![vec![1, 2], vec![2, 2]];
+        assert!(flood_fill::<Dir4, _, _>(&v, (0, 0), |&i| i == 2).is_empty());
+    }
+
+    #[test]
+    fn test_label_components_label_map() {
+        let v = vec![vec![1, 1, 2], vec![2, 1, 2]];
+        let l = label_components::<Dir4, _, _, _>(&v, |_| true, |a, b| a == b);
+        assert_eq!(
+            l.label_map(),
+            [
+                vec![Some(0), Some(0), Some(1)],
+                vec![Some(2), Some(0), Some(1)]
+            ]
+        );
+        assert_eq!(l.label((3, 0)), None);
+        assert_eq!(
+            l.regions().iter().map(|r| r.size()).collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_perimeter_and_sides_by_label() {
+        let v = vec![
+            vec!['A', 'A', 'A', 'A'],
+            vec!['B', 'B', 'C', 'D'],
+            vec!['B', 'B', 'C', 'C'],
+            vec!['E', 'E', 'E', 'C'],
+        ];
+        let l = label_components::<Dir4, _, _, _>(&v, |_| true, |a, b| a == b);
+        let stats: Vec<_> = l
+            .regions()
+            .iter()
+            .map(|r| (r.area(), r.perimeter(), r.sides()))
+            .collect();
+        assert_eq!(
+            stats,
+            [(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+    }
+
+    #[test]
+    fn test_sides_diagonal_touch() {
+        // Two cells touching only at a corner are counted as separate shapes.
+        let r: Region = [(0, 0), (1, 1)].into_iter().collect();
+        assert_eq!(r.perimeter(), 8);
+        assert_eq!(r.sides(), 8);
+    }
+
+    #[test]
+    fn test_label_components_empty() {
+        let v: Vec<Vec<u8>> = Vec::new();
+        assert!(label_components::<Dir4, _, _, _>(&v, |_| true, |_, _| true).is_empty());
+    }
+}
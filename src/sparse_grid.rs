@@ -0,0 +1,266 @@
+use std::collections::{HashMap, hash_map};
+
+use crate::{dir::Direction, grid::Grid, option_min_max::OptionMinMax, point::Point2d};
+
+/// Unbounded 2-dimensional data, where only the occupied cells are stored. Useful when the
+/// interesting area grows over time or is mostly empty. Coordinates are interpreted as in
+/// `position_2d()` (y increases downwards), but can be negative.
+///
+/// The bounding box grows as cells are inserted, but doesn't shrink when they're removed; use
+/// `shrink_bounds()` for that.
+///
+/// ```
+/// use aoclib_rs::{dir::Dir4, point::Point2d, sparse_grid::SparseGrid};
+///
+/// let mut g = SparseGrid::new();
+/// g.insert(Point2d::new(0, 0), '#');
+/// for n in g.neighbours::<Dir4>(Point2d::new(0, 0)) {
+///     g.insert(n, 'o');
+/// }
+/// assert_eq!(g.bounds(), Some((Point2d::new(-1, -1), Point2d::new(1, 1))));
+/// assert_eq!(g.render(|&c| c, '.'), ".o.\no#o\n.o.\n");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2d<i64>, T>,
+    min_x: OptionMinMax<i64>,
+    max_x: OptionMinMax<i64>,
+    min_y: OptionMinMax<i64>,
+    max_y: OptionMinMax<i64>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            min_x: OptionMinMax::NONE,
+            max_x: OptionMinMax::NONE,
+            min_y: OptionMinMax::NONE,
+            max_y: OptionMinMax::NONE,
+        }
+    }
+
+    /// Creates a `SparseGrid` from the cells of `grid` for which `f` returns `Some`.
+    ///
+    /// ```
+    /// use aoclib_rs::{grid::Grid, point::Point2d, sparse_grid::SparseGrid};
+    /// let g = Grid::parse(&["#.", ".#"], |c| c).unwrap();
+    /// let s = SparseGrid::from_grid(&g, |&c| if c == '#' { Some(()) } else { None });
+    /// assert_eq!(s.len(), 2);
+    /// assert!(s.contains(Point2d::new(1, 1)));
+    /// ```
+    pub fn from_grid<U, F>(grid: &Grid<U>, mut f: F) -> Self
+    where
+        F: FnMut(&U) -> Option<T>,
+    {
+        grid.iter()
+            .filter_map(|((x, y), c)| {
+                f(c).map(|v| {
+                    (
+                        Point2d::new(i64::try_from(x).unwrap(), i64::try_from(y).unwrap()),
+                        v,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Inserts `val` at `p`, returning the previous value there, if any.
+    pub fn insert(&mut self, p: Point2d<i64>, val: T) -> Option<T> {
+        self.min_x = self.min_x.min(p.x());
+        self.max_x = self.max_x.max(p.x());
+        self.min_y = self.min_y.min(p.y());
+        self.max_y = self.max_y.max(p.y());
+        self.cells.insert(p, val)
+    }
+
+    /// Removes and returns the value at `p`, if any. Doesn't shrink the bounding box.
+    pub fn remove(&mut self, p: Point2d<i64>) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn get(&self, p: Point2d<i64>) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point2d<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn contains(&self, p: Point2d<i64>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// Number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Iterates through the occupied cells, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2d<i64>, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    /// Returns the top-left and bottom-right corners (inclusive) of the bounding box, or `None`
+    /// if nothing has ever been inserted (or nothing remains after `shrink_bounds()`).
+    pub fn bounds(&self) -> Option<(Point2d<i64>, Point2d<i64>)> {
+        Some((
+            Point2d::new(self.min_x.get()?, self.min_y.get()?),
+            Point2d::new(self.max_x.get()?, self.max_y.get()?),
+        ))
+    }
+
+    /// Width of the bounding box, or `0` if there is none.
+    pub fn width(&self) -> i64 {
+        self.bounds().map_or(0, |(min, max)| max.x() - min.x() + 1)
+    }
+
+    /// Height of the bounding box, or `0` if there is none.
+    pub fn height(&self) -> i64 {
+        self.bounds().map_or(0, |(min, max)| max.y() - min.y() + 1)
+    }
+
+    /// Shrinks the bounding box to fit only the currently-occupied cells.
+    ///
+    /// ```
+    /// use aoclib_rs::{point::Point2d, sparse_grid::SparseGrid};
+    /// let mut g = SparseGrid::new();
+    /// g.insert(Point2d::new(0, 0), ());
+    /// g.insert(Point2d::new(5, 5), ());
+    /// g.remove(Point2d::new(5, 5));
+    /// assert_eq!(g.width(), 6);
+    /// g.shrink_bounds();
+    /// assert_eq!(g.width(), 1);
+    /// ```
+    pub fn shrink_bounds(&mut self) {
+        self.min_x = OptionMinMax::NONE;
+        self.max_x = OptionMinMax::NONE;
+        self.min_y = OptionMinMax::NONE;
+        self.max_y = OptionMinMax::NONE;
+        for p in self.cells.keys() {
+            self.min_x = self.min_x.min(p.x());
+            self.max_x = self.max_x.max(p.x());
+            self.min_y = self.min_y.min(p.y());
+            self.max_y = self.max_y.max(p.y());
+        }
+    }
+
+    /// Iterates through all neighbours of `p`, using the directions of `D`. Since the
+    /// `SparseGrid` is unbounded, every neighbour is valid.
+    pub fn neighbours<D: Direction>(
+        &self,
+        p: Point2d<i64>,
+    ) -> impl Iterator<Item = Point2d<i64>> + use<D, T> {
+        D::iter().map(move |d| {
+            let (dx, dy) = d.delta();
+            Point2d::new(p.x() + i64::from(dx), p.y() + i64::from(dy))
+        })
+    }
+
+    /// Iterates through the occupied neighbours of `p`, using the directions of `D`.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::Dir8, point::Point2d, sparse_grid::SparseGrid};
+    /// let mut g = SparseGrid::new();
+    /// g.insert(Point2d::new(0, 0), 'a');
+    /// g.insert(Point2d::new(1, 1), 'b');
+    /// g.insert(Point2d::new(3, 3), 'c');
+    /// assert_eq!(
+    ///     g.occupied_neighbours::<Dir8>(Point2d::new(0, 0)).collect::<Vec<_>>(),
+    ///     [(Point2d::new(1, 1), &'b')]
+    /// );
+    /// ```
+    pub fn occupied_neighbours<D: Direction>(
+        &self,
+        p: Point2d<i64>,
+    ) -> impl Iterator<Item = (Point2d<i64>, &T)> {
+        self.neighbours::<D>(p)
+            .filter_map(|n| self.get(n).map(|v| (n, v)))
+    }
+
+    /// Renders the bounding box as a string, one line per row (each terminated with `'\n'`),
+    /// using `glyph` for occupied cells and `empty` for unoccupied ones.
+    pub fn render<F>(&self, glyph: F, empty: char) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let Some((min, max)) = self.bounds() else {
+            return String::new();
+        };
+
+        let mut s = String::new();
+        for y in min.y()..=max.y() {
+            for x in min.x()..=max.x() {
+                s.push(self.get(Point2d::new(x, y)).map_or(empty, &glyph));
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<(Point2d<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2d<i64>, T)>>(iter: I) -> Self {
+        let mut g = Self::new();
+        for (p, v) in iter {
+            g.insert(p, v);
+        }
+        g
+    }
+}
+
+impl<T> IntoIterator for SparseGrid<T> {
+    type Item = (Point2d<i64>, T);
+    type IntoIter = hash_map::IntoIter<Point2d<i64>, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty() {
+        let g: SparseGrid<char> = SparseGrid::new();
+        assert!(g.is_empty());
+        assert_eq!(g.bounds(), None);
+        assert_eq!(g.width(), 0);
+        assert_eq!(g.render(|&c| c, '.'), "");
+    }
+
+    #[test]
+    fn test_negative_bounds_render() {
+        let g: SparseGrid<u8> = [(Point2d::new(-2, -1), 1), (Point2d::new(0, 0), 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(g.bounds(), Some((Point2d::new(-2, -1), Point2d::new(0, 0))));
+        assert_eq!((g.width(), g.height()), (3, 2));
+        assert_eq!(
+            g.render(|&v| char::from_digit(u32::from(v), 10).unwrap(), ' '),
+            "1  \n  2\n"
+        );
+    }
+
+    #[test]
+    fn test_shrink_bounds_all_removed() {
+        let mut g = SparseGrid::new();
+        g.insert(Point2d::new(3, 4), ());
+        g.remove(Point2d::new(3, 4));
+        assert!(g.bounds().is_some());
+        g.shrink_bounds();
+        assert_eq!(g.bounds(), None);
+    }
+}
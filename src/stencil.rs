@@ -0,0 +1,178 @@
+use crate::{abs, dir::Direction, point::Point2d};
+
+/// An arbitrary neighbourhood, described as a list of `(dx, dy)` offsets (interpreted as in
+/// `position_2d()`). Useful for neighbourhoods which don't fit a `Direction`, such as knight moves
+/// or every cell within some distance.
+///
+/// ```
+/// use aoclib_rs::stencil::Stencil;
+/// let s = Stencil::new(vec![(0, -2), (2, 0)]);
+/// assert_eq!(
+///     s.iter_valid_usizes((1, 3), (4, 4)).collect::<Vec<_>>(),
+///     [(1, 1), (3, 3)]
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Stencil {
+    offsets: Vec<(i64, i64)>,
+}
+
+impl Stencil {
+    pub fn new(offsets: Vec<(i64, i64)>) -> Self {
+        Self { offsets }
+    }
+
+    /// The `Stencil` with one offset per value of `D`, in the order of `D::iter()`.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::Dir4, stencil::Stencil};
+    /// assert_eq!(
+    ///     Stencil::from_direction::<Dir4>().offsets(),
+    ///     [(0, -1), (1, 0), (0, 1), (-1, 0)]
+    /// );
+    /// ```
+    pub fn from_direction<D: Direction>() -> Self {
+        Self::new(
+            D::iter()
+                .map(|d| {
+                    let (dx, dy) = d.delta();
+                    (i64::from(dx), i64::from(dy))
+                })
+                .collect(),
+        )
+    }
+
+    /// The 8 moves of a chess knight.
+    pub fn knight() -> Self {
+        Self::new(vec![
+            (1, -2),
+            (2, -1),
+            (2, 1),
+            (1, 2),
+            (-1, 2),
+            (-2, 1),
+            (-2, -1),
+            (-1, -2),
+        ])
+    }
+
+    /// Every offset with a Manhattan distance of between 1 and `r` inclusive, in left-to-right,
+    /// top-to-bottom order.
+    ///
+    /// ```
+    /// use aoclib_rs::stencil::Stencil;
+    /// assert_eq!(Stencil::manhattan_ball(1).len(), 4);
+    /// assert_eq!(Stencil::manhattan_ball(2).len(), 12);
+    /// ```
+    pub fn manhattan_ball(r: i64) -> Self {
+        Self::ball(r, |dx, dy| abs(dx) + abs(dy) <= r)
+    }
+
+    /// Every offset with a Chebyshev (chessboard) distance of between 1 and `r` inclusive, in
+    /// left-to-right, top-to-bottom order.
+    ///
+    /// ```
+    /// use aoclib_rs::stencil::Stencil;
+    /// assert_eq!(Stencil::chebyshev_ball(1).len(), 8);
+    /// assert_eq!(Stencil::chebyshev_ball(2).len(), 24);
+    /// ```
+    pub fn chebyshev_ball(r: i64) -> Self {
+        Self::ball(r, |_, _| true)
+    }
+
+    fn ball<F>(r: i64, mut include: F) -> Self
+    where
+        F: FnMut(i64, i64) -> bool,
+    {
+        let mut offsets = Vec::new();
+        for dy in -r..=r {
+            for dx in -r..=r {
+                if (dx, dy) != (0, 0) && include(dx, dy) {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+        Self::new(offsets)
+    }
+
+    pub fn offsets(&self) -> &[(i64, i64)] {
+        &self.offsets
+    }
+
+    /// Number of offsets.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Similar to `Direction::iter_valid_usizes_deltas()`: iterates through `curr` offset by each
+    /// offset in turn, skipping any result which is negative or not less than `size` in the
+    /// respective dimension.
+    pub fn iter_valid_usizes(
+        &self,
+        curr: (usize, usize),
+        size: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets.iter().filter_map(move |&(dx, dy)| {
+            let x = usize::try_from(i64::try_from(curr.0).ok()? + dx).ok()?;
+            let y = usize::try_from(i64::try_from(curr.1).ok()? + dy).ok()?;
+            if x < size.0 && y < size.1 {
+                Some((x, y))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Iterates through `p` offset by each offset in turn, without any bounds checking.
+    pub fn iter_points(&self, p: Point2d<i64>) -> impl Iterator<Item = Point2d<i64>> {
+        self.offsets
+            .iter()
+            .map(move |&(dx, dy)| Point2d::new(p.x() + dx, p.y() + dy))
+    }
+}
+
+impl FromIterator<(i64, i64)> for Stencil {
+    fn from_iter<I: IntoIterator<Item = (i64, i64)>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dir::Dir8;
+
+    #[test]
+    fn test_knight_corner() {
+        let mut v: Vec<_> = Stencil::knight()
+            .iter_valid_usizes((0, 0), (8, 8))
+            .collect();
+        v.sort();
+        assert_eq!(v, [(1, 2), (2, 1)]);
+    }
+
+    #[test]
+    fn test_chebyshev_matches_dir8() {
+        let mut a = Stencil::chebyshev_ball(1).offsets().to_vec();
+        let mut b = Stencil::from_direction::<Dir8>().offsets().to_vec();
+        a.sort();
+        b.sort();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_manhattan_ball_radius_zero() {
+        assert!(Stencil::manhattan_ball(0).is_empty());
+        assert_eq!(
+            Stencil::manhattan_ball(20)
+                .iter_points(Point2d::new(0, 0))
+                .count(),
+            2 * 20 * 21
+        );
+    }
+}
@@ -0,0 +1,274 @@
+/// Returns the transpose of 2-dimensional data (interpreted as in `position_2d()`), ie, the cell at
+/// `(x, y)` moves to `(y, x)`. The caller is responsible for making sure each row is the same
+/// size. Transposing is its own inverse, except that rows of zero width transpose to an empty
+/// `Vec`, losing the height.
+///
+/// ```
+/// use aoclib_rs::transform::transpose;
+/// assert_eq!(
+///     transpose(&[vec![1, 2, 3], vec![4, 5, 6]]),
+///     [vec![1, 4], vec![2, 5], vec![3, 6]]
+/// );
+/// ```
+pub fn transpose<T: Clone>(v: &[Vec<T>]) -> Vec<Vec<T>> {
+    let width = v.first().map_or(0, |row| row.len());
+    (0..width)
+        .map(|x| v.iter().map(|row| row[x].clone()).collect())
+        .collect()
+}
+
+/// Rotates 2-dimensional data (interpreted as in `position_2d()`) "right" or clockwise by 90
+/// degrees, consistent with `Rotate90::rotate_right_90()`: whatever was at the top ends up on the
+/// right. The caller is responsible for making sure each row is the same size. The inverse is
+/// `rotate_left()`. Rows of zero width rotate to an empty `Vec`, losing the height.
+///
+/// ```
+/// use aoclib_rs::transform::rotate_right;
+/// assert_eq!(
+///     rotate_right(&[vec![1, 2, 3], vec![4, 5, 6]]),
+///     [vec![4, 1], vec![5, 2], vec![6, 3]]
+/// );
+/// ```
+pub fn rotate_right<T: Clone>(v: &[Vec<T>]) -> Vec<Vec<T>> {
+    let width = v.first().map_or(0, |row| row.len());
+    (0..width)
+        .map(|x| v.iter().rev().map(|row| row[x].clone()).collect())
+        .collect()
+}
+
+/// Rotates 2-dimensional data (interpreted as in `position_2d()`) "left" or counter-clockwise by
+/// 90 degrees, consistent with `Rotate90::rotate_left_90()`: whatever was at the top ends up on
+/// the left. The caller is responsible for making sure each row is the same size. The inverse is
+/// `rotate_right()`. Rows of zero width rotate to an empty `Vec`, losing the height.
+///
+/// ```
+/// use aoclib_rs::transform::rotate_left;
+/// assert_eq!(
+///     rotate_left(&[vec![1, 2, 3], vec![4, 5, 6]]),
+///     [vec![3, 6], vec![2, 5], vec![1, 4]]
+/// );
+/// ```
+pub fn rotate_left<T: Clone>(v: &[Vec<T>]) -> Vec<Vec<T>> {
+    let width = v.first().map_or(0, |row| row.len());
+    (0..width)
+        .rev()
+        .map(|x| v.iter().map(|row| row[x].clone()).collect())
+        .collect()
+}
+
+/// Rotates 2-dimensional data (interpreted as in `position_2d()`) by 180 degrees. Rotating by 180
+/// degrees is its own inverse.
+///
+/// ```
+/// use aoclib_rs::transform::rotate_180;
+/// assert_eq!(
+///     rotate_180(&[vec![1, 2, 3], vec![4, 5, 6]]),
+///     [vec![6, 5, 4], vec![3, 2, 1]]
+/// );
+/// ```
+pub fn rotate_180<T: Clone>(v: &[Vec<T>]) -> Vec<Vec<T>> {
+    v.iter()
+        .rev()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// Mirrors 2-dimensional data (interpreted as in `position_2d()`) left-to-right. Flipping is its
+/// own inverse.
+///
+/// ```
+/// use aoclib_rs::transform::flip_horizontal;
+/// assert_eq!(
+///     flip_horizontal(&[vec![1, 2, 3], vec![4, 5, 6]]),
+///     [vec![3, 2, 1], vec![6, 5, 4]]
+/// );
+/// ```
+pub fn flip_horizontal<T: Clone>(v: &[Vec<T>]) -> Vec<Vec<T>> {
+    v.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+/// Mirrors 2-dimensional data (interpreted as in `position_2d()`) top-to-bottom. Flipping is its
+/// own inverse.
+///
+/// ```
+/// use aoclib_rs::transform::flip_vertical;
+/// assert_eq!(
+///     flip_vertical(&[vec![1, 2, 3], vec![4, 5, 6]]),
+///     [vec![4, 5, 6], vec![1, 2, 3]]
+/// );
+/// ```
+pub fn flip_vertical<T: Clone>(v: &[Vec<T>]) -> Vec<Vec<T>> {
+    v.iter().rev().cloned().collect()
+}
+
+/// One of the 8 orientations (rotations and reflections) of 2-dimensional data. An `Orientation`
+/// is applied by first flipping horizontally (if `flipped()`), then rotating right (clockwise) by
+/// 90 degrees `rotations()` times.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    rotations: u8,
+    flipped: bool,
+}
+
+impl Orientation {
+    /// The `Orientation` which leaves data unchanged.
+    pub const IDENTITY: Self = Self::new(0, false);
+
+    /// `rotations` is taken modulo 4.
+    pub const fn new(rotations: u8, flipped: bool) -> Self {
+        Self {
+            rotations: rotations % 4,
+            flipped,
+        }
+    }
+
+    /// Iterates through all 8 `Orientation`s, starting with `IDENTITY` and the other unflipped
+    /// ones.
+    ///
+    /// ```
+    /// use aoclib_rs::transform::Orientation;
+    /// assert_eq!(Orientation::iter().count(), 8);
+    /// assert_eq!(Orientation::iter().next(), Some(Orientation::IDENTITY));
+    /// ```
+    pub fn iter() -> impl Iterator<Item = Self> {
+        [false, true]
+            .into_iter()
+            .flat_map(|flipped| (0..4).map(move |rotations| Self::new(rotations, flipped)))
+    }
+
+    /// Number of 90 degree clockwise rotations, in `0..4`.
+    pub fn rotations(&self) -> u8 {
+        self.rotations
+    }
+
+    pub fn flipped(&self) -> bool {
+        self.flipped
+    }
+
+    /// Returns the `Orientation` which undoes `self`.
+    ///
+    /// ```
+    /// use aoclib_rs::transform::Orientation;
+    /// let v = vec![vec![1, 2, 3], vec![4, 5, 6]];
+    /// for o in Orientation::iter() {
+    ///     assert_eq!(o.inverse().apply(&o.apply(&v)), v);
+    /// }
+    /// ```
+    pub fn inverse(&self) -> Self {
+        if self.flipped {
+            // Flipping, then rotating right n times is the same as rotating left n times, then
+            // flipping, so undoing it is the same operation again.
+            *self
+        } else {
+            Self::new(4 - self.rotations, false)
+        }
+    }
+
+    /// Applies the `Orientation` to 2-dimensional data (interpreted as in `position_2d()`). The
+    /// caller is responsible for making sure each row is the same size.
+    ///
+    /// ```
+    /// use aoclib_rs::transform::Orientation;
+    /// assert_eq!(
+    ///     Orientation::new(1, true).apply(&[vec![1, 2, 3], vec![4, 5, 6]]),
+    ///     [vec![6, 3], vec![5, 2], vec![4, 1]]
+    /// );
+    /// ```
+    pub fn apply<T: Clone>(&self, v: &[Vec<T>]) -> Vec<Vec<T>> {
+        let mut r = if self.flipped {
+            flip_horizontal(v)
+        } else {
+            v.to_vec()
+        };
+        for _ in 0..self.rotations {
+            r = rotate_right(&r);
+        }
+        r
+    }
+
+    /// Given the `(x, y)` position of a cell in data of size `(width, height)`, returns the
+    /// position that cell ends up at after `apply()`ing the `Orientation`.
+    ///
+    /// ```
+    /// use aoclib_rs::transform::Orientation;
+    /// let v = vec![vec![1, 2, 3], vec![4, 5, 6]];
+    /// let o = Orientation::new(3, true);
+    /// let (x, y) = o.map_pos((2, 0), (3, 2));
+    /// assert_eq!(o.apply(&v)[y][x], 3);
+    /// ```
+    pub fn map_pos(&self, pos: (usize, usize), size: (usize, usize)) -> (usize, usize) {
+        let (mut x, mut y) = pos;
+        let (mut width, mut height) = size;
+        if self.flipped {
+            x = width - 1 - x;
+        }
+        for _ in 0..self.rotations {
+            (x, y) = (height - 1 - y, x);
+            (width, height) = (height, width);
+        }
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Vec<u8>> {
+        vec![vec![1, 2, 3], vec![4, 5, 6]]
+    }
+
+    #[test]
+    fn test_rotate_inverses() {
+        let v = sample();
+        assert_eq!(rotate_left(&rotate_right(&v)), v);
+        assert_eq!(rotate_right(&rotate_left(&v)), v);
+        assert_eq!(rotate_180(&rotate_180(&v)), v);
+        assert_eq!(rotate_right(&rotate_right(&v)), rotate_180(&v));
+        assert_eq!(transpose(&transpose(&v)), v);
+        assert_eq!(flip_horizontal(&flip_horizontal(&v)), v);
+        assert_eq!(flip_vertical(&flip_vertical(&v)), v);
+    }
+
+    #[test]
+    fn test_empty() {
+        let v: Vec<Vec<u8>> = Vec::new();
+        assert!(transpose(&v).is_empty());
+        assert!(rotate_right(&v).is_empty());
+        assert!(rotate_left(&v).is_empty());
+        assert!(rotate_180(&v).is_empty());
+
+        let v: Vec<Vec<u8>> = vec![Vec::new(), Vec::new()];
+        assert!(transpose(&v).is_empty());
+        assert!(rotate_right(&v).is_empty());
+        assert!(rotate_left(&v).is_empty());
+        assert_eq!(rotate_180(&v), v);
+    }
+
+    #[test]
+    fn test_orientation_all_distinct() {
+        let v = sample();
+        let mut all: Vec<_> = Orientation::iter().map(|o| o.apply(&v)).collect();
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), 8);
+    }
+
+    #[test]
+    fn test_orientation_map_pos() {
+        let v = sample();
+        for o in Orientation::iter() {
+            let applied = o.apply(&v);
+            let size = (applied[0].len(), applied.len());
+            for (y, row) in v.iter().enumerate() {
+                for (x, c) in row.iter().enumerate() {
+                    let (nx, ny) = o.map_pos((x, y), (3, 2));
+                    assert!(nx < size.0 && ny < size.1);
+                    assert_eq!(applied[ny][nx], *c);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::{collections::HashSet, hash::Hash};
+
+use crate::dir::Rotate90;
+
+/// An agent with a position and a facing direction, which moves forward and turns relative to
+/// its facing. Every cell it occupies is recorded, as is every `(position, facing)` state, so that
+/// revisiting a state (and therefore being stuck in a loop, if its behaviour only depends on its
+/// state) can be detected.
+///
+/// Positions are signed so that walking off the edge of the area of interest can be detected with
+/// `pos_usizes()` rather than panicking. Coordinates are interpreted as in `position_2d()`.
+///
+/// ```
+/// use aoclib_rs::{dir::Dir4, walker::Walker};
+///
+/// // A guard who turns right whenever something is in the way.
+/// let map = ["..#.", "...#", "..^.", "..#."];
+/// let blocked = |(x, y): (usize, usize)| map[y].as_bytes()[x] == b'#';
+/// let size = (4, 4);
+///
+/// let mut guard = Walker::from_usizes((2, 2), Dir4::Up);
+/// let looped = loop {
+///     let Some(ahead) = guard.ahead_usizes(size) else {
+///         break false;
+///     };
+///     let revisited = if blocked(ahead) {
+///         guard.turn_right()
+///     } else {
+///         guard.forward()
+///     };
+///     if revisited {
+///         break true;
+///     }
+/// };
+/// assert!(!looped);
+/// assert_eq!(guard.visited().len(), 4);
+/// assert_eq!(guard.pos(), (0, 2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Walker<D> {
+    pos: (i64, i64),
+    facing: D,
+    visited: HashSet<(i64, i64)>,
+    states: HashSet<((i64, i64), D)>,
+    looped: bool,
+}
+
+impl<D> Walker<D>
+where
+    D: Rotate90 + Eq + Hash,
+{
+    pub fn new(pos: (i64, i64), facing: D) -> Self {
+        Self {
+            pos,
+            facing,
+            visited: HashSet::from([pos]),
+            states: HashSet::from([(pos, facing)]),
+            looped: false,
+        }
+    }
+
+    /// Can panic if `pos` doesn't fit in `i64`s.
+    pub fn from_usizes(pos: (usize, usize), facing: D) -> Self {
+        Self::new(
+            (i64::try_from(pos.0).unwrap(), i64::try_from(pos.1).unwrap()),
+            facing,
+        )
+    }
+
+    pub fn pos(&self) -> (i64, i64) {
+        self.pos
+    }
+
+    pub fn facing(&self) -> D {
+        self.facing
+    }
+
+    /// Returns the current position as `usize`s, or `None` if it's outside the area described by
+    /// `size`.
+    pub fn pos_usizes(&self, size: (usize, usize)) -> Option<(usize, usize)> {
+        to_usizes(self.pos, size)
+    }
+
+    /// Returns the position one unit ahead, without moving.
+    pub fn ahead(&self) -> (i64, i64) {
+        let (dx, dy) = self.facing.delta();
+        (self.pos.0 + i64::from(dx), self.pos.1 + i64::from(dy))
+    }
+
+    /// Returns the position one unit ahead as `usize`s, or `None` if it's outside the area
+    /// described by `size`.
+    pub fn ahead_usizes(&self, size: (usize, usize)) -> Option<(usize, usize)> {
+        to_usizes(self.ahead(), size)
+    }
+
+    /// Every position ever occupied, including the starting one.
+    pub fn visited(&self) -> &HashSet<(i64, i64)> {
+        &self.visited
+    }
+
+    /// Whether any move or turn so far has led to a previously-seen `(position, facing)` state.
+    pub fn has_looped(&self) -> bool {
+        self.looped
+    }
+
+    /// Turns 90 degrees counter-clockwise. Returns whether the resulting state was seen before.
+    pub fn turn_left(&mut self) -> bool {
+        self.facing = self.facing.rotate_left_90();
+        self.record()
+    }
+
+    /// Turns 90 degrees clockwise. Returns whether the resulting state was seen before.
+    pub fn turn_right(&mut self) -> bool {
+        self.facing = self.facing.rotate_right_90();
+        self.record()
+    }
+
+    /// Turns 180 degrees. Returns whether the resulting state was seen before.
+    pub fn turn_around(&mut self) -> bool {
+        self.facing = self.facing.opposite();
+        self.record()
+    }
+
+    /// Moves one unit forward. Returns whether the resulting state was seen before.
+    pub fn forward(&mut self) -> bool {
+        self.pos = self.ahead();
+        self.record()
+    }
+
+    /// Moves `n` units forward, one at a time. Returns whether any of the intermediate states
+    /// were seen before.
+    ///
+    /// ```
+    /// use aoclib_rs::{dir::Dir4, walker::Walker};
+    /// let mut w = Walker::new((0, 0), Dir4::Right);
+    /// assert!(!w.forward_n(3));
+    /// w.turn_around();
+    /// assert!(!w.forward_n(2));
+    /// w.turn_around();
+    /// assert!(w.forward_n(1));
+    /// assert_eq!(w.pos(), (2, 0));
+    /// assert_eq!(w.visited().len(), 4);
+    /// ```
+    pub fn forward_n(&mut self, n: usize) -> bool {
+        let mut revisited = false;
+        for _ in 0..n {
+            revisited |= self.forward();
+        }
+        revisited
+    }
+
+    fn record(&mut self) -> bool {
+        self.visited.insert(self.pos);
+        let revisited = !self.states.insert((self.pos, self.facing));
+        self.looped |= revisited;
+        revisited
+    }
+}
+
+fn to_usizes(pos: (i64, i64), size: (usize, usize)) -> Option<(usize, usize)> {
+    let x = usize::try_from(pos.0).ok().filter(|&x| x < size.0)?;
+    let y = usize::try_from(pos.1).ok().filter(|&y| y < size.1)?;
+    Some((x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::dir::{Dir4, Dir8};
+
+    #[test]
+    fn test_square_loop() {
+        let mut w = Walker::new((0, 0), Dir4::Up);
+        for _ in 0..4 {
+            assert!(!w.forward_n(2));
+            let revisited = w.turn_right();
+            assert_eq!(revisited, w.pos() == (0, 0));
+        }
+        assert!(w.has_looped());
+        assert_eq!(w.visited().len(), 8);
+        assert_eq!(w.pos_usizes((3, 3)), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let mut w = Walker::from_usizes((0, 1), Dir8::UpLeft);
+        w.forward();
+        assert_eq!(w.pos(), (-1, 0));
+        assert_eq!(w.pos_usizes((5, 5)), None);
+        w.turn_left();
+        assert_eq!(w.facing(), Dir8::DownLeft);
+    }
+}